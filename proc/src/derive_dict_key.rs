@@ -0,0 +1,95 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::internals::{ast, attr, ctxt};
+use crate::{bound, Derive};
+
+pub fn impl_derive(input: syn::DeriveInput) -> Result<TokenStream, Vec<syn::Error>> {
+    let cx = ctxt::Ctxt::new();
+    let container = match ast::Container::from_ast(&cx, &input, Derive::Debug) {
+        Some(container) => container,
+        None => return Err(cx.check().unwrap_err()),
+    };
+
+    let fields = match &container.data {
+        ast::Data::Struct(style, fields) => {
+            if !matches!(container.attrs.tlb_tag, attr::ContainerTag::None) {
+                cx.error_spanned_by(&input, "dict keys can't have tags");
+            }
+            if container.attrs.tlb_validate_with.is_some() {
+                cx.error_spanned_by(&input, "dict keys can't have a validation function");
+            }
+            for field in fields {
+                if field.attrs.since_tag.is_some() {
+                    cx.error_spanned_by(field.original, "since_tag is not supported for dict keys");
+                }
+            }
+            Some((*style, fields))
+        }
+        ast::Data::Enum(_) => {
+            cx.error_spanned_by(&input, "DictKey can only be derived for structs");
+            None
+        }
+    };
+    cx.check()?;
+    let Some((style, fields)) = fields else {
+        return Ok(TokenStream::new());
+    };
+
+    let ident = &container.ident;
+    let generics = bound::without_default(container.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let field_bits = fields
+        .iter()
+        .map(|field| {
+            let ty = field.ty;
+            quote!(<#ty as ::everscale_types::dict::DictKey>::BITS)
+        })
+        .collect::<Vec<_>>();
+
+    // Fields are stored one after another, so each field offset
+    // is a sum of bit lengths of all previous fields
+    let read_fields = fields.iter().enumerate().map(|(i, field)| {
+        let ty = field.ty;
+        let var = format_ident!("__field{}", i);
+        let prev_bits = &field_bits[..i];
+        quote! {
+            let #var = match ::everscale_types::dict::read_key_part::<#ty>(
+                __raw_data,
+                0 #(+ #prev_bits)*,
+            ) {
+                ::core::option::Option::Some(value) => value,
+                ::core::option::Option::None => return ::core::option::Option::None,
+            };
+        }
+    });
+
+    let vars = (0..fields.len()).map(|i| format_ident!("__field{}", i));
+    let construct = match style {
+        ast::Style::Struct => {
+            let members = fields.iter().map(|field| &field.member);
+            quote!(Self { #(#members: #vars),* })
+        }
+        ast::Style::Tuple => quote!(Self(#(#vars),*)),
+        ast::Style::Unit => quote!(Self),
+    };
+
+    let result = quote! {
+        #[automatically_derived]
+        impl #impl_generics ::everscale_types::dict::DictKey for #ident #ty_generics #where_clause {
+            const BITS: u16 = {
+                let bits = 0 #(+ #field_bits)*;
+                assert!(bits <= ::everscale_types::cell::MAX_BIT_LEN, "dict key is too big");
+                bits
+            };
+
+            fn from_raw_data(__raw_data: &[u8; 128]) -> ::core::option::Option<Self> {
+                #(#read_fields)*
+                ::core::option::Option::Some(#construct)
+            }
+        }
+    };
+
+    Ok(result)
+}
@@ -2,6 +2,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 
 mod bound;
+mod derive_dict_key;
 mod derive_load;
 mod derive_store;
 mod internals;
@@ -29,6 +30,20 @@ pub fn derive_store(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Implements `DictKey` for the struct.
+///
+/// Fields are read one after another, so all of them must implement `DictKey`.
+/// This derive doesn't implement `Store`, use `#[derive(Store, DictKey)]` to get
+/// the matching key layout. Tags and validation functions are not allowed
+/// since they would break the key layout.
+#[proc_macro_derive(DictKey, attributes(tlb))]
+pub fn derive_dict_key(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_dict_key::impl_derive(input)
+        .unwrap_or_else(to_compile_errors)
+        .into()
+}
+
 fn to_compile_errors(errors: Vec<syn::Error>) -> proc_macro2::TokenStream {
     let compile_errors = errors.iter().map(syn::Error::to_compile_error);
     quote!(#(#compile_errors)*)
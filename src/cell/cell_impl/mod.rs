@@ -4,8 +4,7 @@ use std::mem::MaybeUninit;
 #[cfg(feature = "stats")]
 use super::CellTreeStats;
 use super::{
-    Cell, CellDescriptor, CellFamily, CellImpl, CellInner, DynCell, HashBytes, EMPTY_CELL_HASH,
-    MAX_REF_COUNT,
+    Cell, CellDescriptor, CellImpl, CellInner, DynCell, HashBytes, EMPTY_CELL_HASH, MAX_REF_COUNT,
 };
 use crate::util::TryAsMut;
 
@@ -419,11 +418,12 @@ impl<const N: usize> CellImpl for OrdinaryCell<N> {
     }
 
     fn virtualize(&self) -> &DynCell {
-        if self.header.descriptor.level_mask().is_empty() {
-            self
-        } else {
-            VirtualCellWrapper::wrap(self)
-        }
+        let level = max_level(self.header.descriptor);
+        self.virtualize_at(level.saturating_sub(1))
+    }
+
+    fn virtualize_at(&self, level: u8) -> &DynCell {
+        virtualize_at_level(self, level)
     }
 
     fn hash(&self, level: u8) -> &HashBytes {
@@ -566,7 +566,12 @@ impl<const N: usize> CellImpl for PrunedBranch<N> {
     }
 
     fn virtualize(&self) -> &DynCell {
-        VirtualCellWrapper::wrap(self)
+        let level = max_level(self.header.descriptor);
+        self.virtualize_at(level.saturating_sub(1))
+    }
+
+    fn virtualize_at(&self, level: u8) -> &DynCell {
+        virtualize_at_level(self, level)
     }
 
     fn hash(&self, level: u8) -> &HashBytes {
@@ -617,8 +622,23 @@ impl<const N: usize> CellImpl for PrunedBranch<N> {
     }
 }
 
-#[repr(transparent)]
-pub struct VirtualCell<T>(T);
+/// An owned virtualized cell.
+pub struct VirtualCell<T> {
+    cell: T,
+    /// Virtualization level of the cell.
+    /// `None` means one level below the level of the inner cell.
+    level: Option<u8>,
+}
+
+impl<T: AsRef<DynCell>> VirtualCell<T> {
+    #[inline]
+    fn view(&self) -> &DynCell {
+        match self.level {
+            None => self.cell.as_ref().virtualize(),
+            Some(level) => self.cell.as_ref().virtualize_at(level),
+        }
+    }
+}
 
 impl<#[cfg(not(feature = "sync"))] T, #[cfg(feature = "sync")] T: Send + Sync> CellImpl
     for VirtualCell<T>
@@ -631,49 +651,51 @@ where
     }
 
     fn descriptor(&self) -> CellDescriptor {
-        self.0.as_ref().descriptor()
+        self.cell.as_ref().descriptor()
     }
 
     fn data(&self) -> &[u8] {
-        self.0.as_ref().data()
+        self.cell.as_ref().data()
     }
 
     fn bit_len(&self) -> u16 {
-        self.0.as_ref().bit_len()
+        self.cell.as_ref().bit_len()
     }
 
     fn reference(&self, index: u8) -> Option<&DynCell> {
-        Some(self.0.as_ref().reference(index)?.virtualize())
+        self.view().reference(index)
     }
 
     fn reference_cloned(&self, index: u8) -> Option<Cell> {
-        Some(Cell::virtualize(self.0.as_ref().reference_cloned(index)?))
+        self.view().reference_cloned(index)
     }
 
     fn virtualize(&self) -> &DynCell {
-        self.0.as_ref().virtualize()
+        self.view().virtualize()
+    }
+
+    fn virtualize_at(&self, level: u8) -> &DynCell {
+        self.cell.as_ref().virtualize_at(level)
     }
 
     fn hash(&self, level: u8) -> &HashBytes {
-        let cell = self.0.as_ref();
-        cell.hash(virtual_hash_index(cell.descriptor(), level, 0))
+        self.view().hash(level)
     }
 
     fn depth(&self, level: u8) -> u16 {
-        let cell = self.0.as_ref();
-        cell.depth(virtual_hash_index(cell.descriptor(), level, 0))
+        self.view().depth(level)
     }
 
     fn take_first_child(&mut self) -> Option<Cell> {
-        self.0.try_as_mut()?.take_first_child()
+        self.cell.try_as_mut()?.take_first_child()
     }
 
     fn take_next_child(&mut self) -> Option<Cell> {
-        self.0.try_as_mut()?.take_next_child()
+        self.cell.try_as_mut()?.take_next_child()
     }
 
     fn replace_first_child(&mut self, parent: Cell) -> ReplacedChild {
-        match self.0.try_as_mut() {
+        match self.cell.try_as_mut() {
             Some(cell) => cell.replace_first_child(parent),
             None => Err(parent),
         }
@@ -681,11 +703,14 @@ where
 
     #[cfg(feature = "stats")]
     fn stats(&self) -> CellTreeStats {
-        self.0.as_ref().stats()
+        self.cell.as_ref().stats()
     }
 }
 
 /// A wrapper type which implements a virtualized cell interface.
+///
+/// All hashes and depths above the level `L` are replaced
+/// with the values at the level `L`.
 #[repr(transparent)]
 pub struct VirtualCellWrapper<T, const L: u8 = 0>(T);
 
@@ -720,25 +745,27 @@ where
     }
 
     fn reference(&self, index: u8) -> Option<&DynCell> {
-        dyn_reference_virtualize(&self.0, index)
+        dyn_reference_virtualize(&self.0, index, L)
     }
 
     fn reference_cloned(&self, index: u8) -> Option<Cell> {
-        dyn_reference_virtualize_cloned(&self.0, index)
+        dyn_reference_virtualize_cloned(&self.0, index, L)
     }
 
     fn virtualize(&self) -> &DynCell {
-        unsafe { virtualize_into_next_wrapper(L, &self.0) }
+        self.0.virtualize_at(L.saturating_sub(1))
+    }
+
+    fn virtualize_at(&self, level: u8) -> &DynCell {
+        self.0.virtualize_at(level)
     }
 
     fn hash(&self, level: u8) -> &HashBytes {
-        self.0
-            .hash(virtual_hash_index(self.0.descriptor(), level, L))
+        self.0.hash(std::cmp::min(level, L))
     }
 
     fn depth(&self, level: u8) -> u16 {
-        self.0
-            .depth(virtual_hash_index(self.0.descriptor(), level, L))
+        self.0.depth(std::cmp::min(level, L))
     }
 
     fn take_first_child(&mut self) -> Option<Cell> {
@@ -760,16 +787,58 @@ where
 }
 
 #[inline(never)]
-fn dyn_reference_virtualize(cell: &DynCell, index: u8) -> Option<&DynCell> {
-    Some(cell.reference(index)?.virtualize())
+fn dyn_reference_virtualize(cell: &DynCell, index: u8, level: u8) -> Option<&DynCell> {
+    let child_level = level + cell.descriptor().is_merkle() as u8;
+    Some(cell.reference(index)?.virtualize_at(child_level))
 }
 
 #[inline(never)]
-fn dyn_reference_virtualize_cloned(cell: &DynCell, index: u8) -> Option<Cell> {
-    Some(Cell::virtualize(cell.reference_cloned(index)?))
+fn dyn_reference_virtualize_cloned(cell: &DynCell, index: u8, level: u8) -> Option<Cell> {
+    let child_level = level + cell.descriptor().is_merkle() as u8;
+    Some(virtualize_cell_at(
+        cell.reference_cloned(index)?,
+        child_level,
+    ))
+}
+
+/// Returns an owned cell virtualized with the specified level.
+fn virtualize_cell_at(cell: Cell, level: u8) -> Cell {
+    if max_level(cell.as_ref().descriptor()) <= level {
+        cell
+    } else {
+        Cell(CellInner::new(VirtualCell {
+            cell,
+            level: Some(level),
+        }))
+    }
+}
+
+/// Returns a cell reference virtualized with the specified level.
+///
+/// Cells with a level not greater than the virtualization level
+/// are returned as is.
+pub(crate) fn virtualize_at_level<
+    #[cfg(not(feature = "sync"))] T: CellImpl + Sized + 'static,
+    #[cfg(feature = "sync")] T: CellImpl + Sized + Send + Sync + 'static,
+>(
+    cell: &T,
+    level: u8,
+) -> &DynCell {
+    if max_level(cell.descriptor()) <= level {
+        cell
+    } else {
+        // SAFETY: level is always less than the max level here
+        unsafe { virtualize_into_wrapper(level, cell) }
+    }
 }
 
-const unsafe fn virtualize_into_next_wrapper<
+/// # Safety
+///
+/// The following must be true:
+/// - `level` must be less than [`LevelMask::MAX_LEVEL`].
+///
+/// [`LevelMask::MAX_LEVEL`]: crate::cell::LevelMask::MAX_LEVEL
+const unsafe fn virtualize_into_wrapper<
     #[cfg(not(feature = "sync"))] T: CellImpl + Sized + 'static,
     #[cfg(feature = "sync")] T: CellImpl + Sized + Send + Sync + 'static,
 >(
@@ -796,10 +865,14 @@ const unsafe fn virtualize_into_next_wrapper<
     where
         T: CellImpl + 'static,
     {
-        const LEVELS: [*const (); 2] = [gen_vtable_ptr::<T, 1>(), gen_vtable_ptr::<T, 2>()];
+        const LEVELS: [*const (); 3] = [
+            gen_vtable_ptr::<T, 0>(),
+            gen_vtable_ptr::<T, 1>(),
+            gen_vtable_ptr::<T, 2>(),
+        ];
     }
 
-    let vtable = Vtable::<T>::LEVELS[(level != 0) as usize];
+    let vtable = Vtable::<T>::LEVELS[level as usize];
 
     // Construct fat pointer with vtable info
     let data = cell as *const T as *const ();
@@ -808,11 +881,9 @@ const unsafe fn virtualize_into_next_wrapper<
     &*ptr
 }
 
-fn virtual_hash_index(descriptor: CellDescriptor, level: u8, offset: u8) -> u8 {
-    descriptor
-        .level_mask()
-        .virtualize(1 + offset)
-        .hash_index(level)
+/// Returns the highest level of the cell.
+pub(crate) fn max_level(descriptor: CellDescriptor) -> u8 {
+    8 - descriptor.level_mask().to_byte().leading_zeros() as u8
 }
 
 fn hash_index(descriptor: CellDescriptor, level: u8) -> u8 {
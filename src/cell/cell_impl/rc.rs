@@ -133,7 +133,7 @@ impl CellFamily for Cell {
         if descriptor.level_mask().is_empty() {
             cell
         } else {
-            Cell(Rc::new(VirtualCell(cell)))
+            Cell(Rc::new(VirtualCell { cell, level: None }))
         }
     }
 }
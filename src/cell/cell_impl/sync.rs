@@ -134,7 +134,7 @@ impl CellFamily for Cell {
         if descriptor.level_mask().is_empty() {
             cell
         } else {
-            Cell(Arc::new(VirtualCell(cell)))
+            Cell(Arc::new(VirtualCell { cell, level: None }))
         }
     }
 }
@@ -134,8 +134,22 @@ pub trait CellImpl {
 
     /// Returns this cell as a virtualized cell, so that all hashes
     /// and depths will have an offset.
+    ///
+    /// Calling this method on an already virtualized cell decreases
+    /// its virtualization level by one.
     fn virtualize(&self) -> &DynCell;
 
+    /// Returns this cell as a virtualized cell with the specified level,
+    /// so that all hashes and depths above this level will be replaced
+    /// with the values at this level.
+    ///
+    /// Children of Merkle cells are virtualized with the next level,
+    /// so that nested Merkle structures are handled correctly.
+    fn virtualize_at(&self, level: u8) -> &DynCell {
+        let _ = level;
+        self.virtualize()
+    }
+
     /// Returns cell hash for the specified level.
     ///
     /// Cell representation hash is the hash at the maximum level ([`LevelMask::MAX_LEVEL`]).
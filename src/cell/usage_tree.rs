@@ -1,4 +1,4 @@
-use super::cell_impl::VirtualCellWrapper;
+use super::cell_impl::{max_level, virtualize_at_level};
 use super::{Cell, CellDescriptor, CellImpl, CellInner, DynCell, HashBytes};
 use crate::util::TryAsMut;
 
@@ -153,7 +153,12 @@ impl CellImpl for UsageCell {
     }
 
     fn virtualize(&self) -> &DynCell {
-        VirtualCellWrapper::wrap(self)
+        let level = max_level(self.cell.descriptor());
+        self.virtualize_at(level.saturating_sub(1))
+    }
+
+    fn virtualize_at(&self, level: u8) -> &DynCell {
+        virtualize_at_level(self, level)
     }
 
     fn hash(&self, level: u8) -> &HashBytes {
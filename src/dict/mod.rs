@@ -4,15 +4,23 @@ use std::ops::ControlFlow;
 
 pub use self::aug::*;
 pub use self::ops::*;
+pub use self::pfx::*;
 pub use self::raw::*;
 pub use self::typed::*;
+pub use self::var::*;
+pub use everscale_types_proc::DictKey;
 
 use crate::cell::*;
 use crate::error::Error;
 
 mod aug;
+mod pfx;
 mod raw;
 mod typed;
+mod var;
+
+#[cfg(feature = "serde")]
+pub mod serde_map;
 
 mod ops {
     pub use self::build::{build_aug_dict_from_sorted_iter, build_dict_from_sorted_iter};
@@ -75,6 +83,30 @@ impl_dict_key! {
     },
 }
 
+/// Creates a key from the raw key data starting at the specified bit offset.
+///
+/// Used by `#[derive(DictKey)]` to read fields of composite keys.
+#[doc(hidden)]
+pub fn read_key_part<K: DictKey>(raw_data: &[u8; 128], offset: u16) -> Option<K> {
+    let byte_offset = (offset / 8) as usize;
+    let shift = offset % 8;
+    if byte_offset == 0 && shift == 0 {
+        return K::from_raw_data(raw_data);
+    }
+
+    let mut data = [0u8; 128];
+    let src = raw_data.get(byte_offset..).unwrap_or_default();
+    if shift == 0 {
+        data[..src.len()].copy_from_slice(src);
+    } else {
+        for (i, byte) in data.iter_mut().take(src.len()).enumerate() {
+            let next = src.get(i + 1).copied().unwrap_or_default();
+            *byte = (src[i] << shift) | (next >> (8 - shift));
+        }
+    }
+    K::from_raw_data(&data)
+}
+
 /// `AugDict` search control flow.
 pub trait SearchByExtra<A> {
     /// Returns if the leaf extra satisfies the condition.
@@ -401,6 +433,54 @@ fn rebuild_aug_dict_from_stack(
     Ok(leaf)
 }
 
+/// Rebuilds the node replacing the child at the specified index.
+fn rebuild_with_child(
+    node: &DynCell,
+    index: u8,
+    child: Cell,
+    context: &dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(builder.store_cell_data(node));
+    for i in 0..node.reference_count() {
+        let cell = if i == index {
+            child.clone()
+        } else {
+            match node.reference_cloned(i) {
+                Some(cell) => cell,
+                None => return Err(Error::CellUnderflow),
+            }
+        };
+        ok!(builder.store_reference(cell));
+    }
+    builder.build_ext(context)
+}
+
+/// Creates an edge from the parent label, the branch bit and the child edge.
+///
+/// Used to collapse a node with a single remaining child.
+fn join_edge(
+    pfx: &CellSlice<'_>,
+    bit: bool,
+    child: &DynCell,
+    key_bit_len: u16,
+    context: &dyn CellContext,
+) -> Result<Cell, Error> {
+    let Some(child_key_bit_len) = key_bit_len.checked_sub(pfx.size_bits() + 1) else {
+        return Err(Error::CellUnderflow);
+    };
+
+    let mut child = ok!(context
+        .load_dyn_cell(child, LoadMode::Full)
+        .and_then(CellSlice::new));
+    let rem = ok!(read_label(&mut child, child_key_bit_len));
+
+    let mut builder = CellBuilder::new();
+    ok!(write_label_parts(pfx, bit, &rem, key_bit_len, &mut builder));
+    ok!(builder.store_slice(child));
+    builder.build_ext(context)
+}
+
 #[derive(Clone, Copy)]
 struct Segment<'a> {
     data: &'a DynCell,
@@ -744,4 +824,70 @@ mod tests {
             assert_eq!(built_from_dict, dict.root);
         }
     }
+
+    #[test]
+    fn derived_dict_key() -> anyhow::Result<()> {
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Store, DictKey)]
+        struct QueueKey {
+            workchain: i8,
+            lt: u64,
+            hash: HashBytes,
+        }
+
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Store, DictKey)]
+        struct Unaligned(bool, u16, bool, QueueKey);
+
+        assert_eq!(QueueKey::BITS, 8 + 64 + 256);
+        assert_eq!(Unaligned::BITS, 1 + 16 + 1 + QueueKey::BITS);
+
+        let keys = [
+            QueueKey {
+                workchain: -1,
+                lt: 123,
+                hash: HashBytes([0xaa; 32]),
+            },
+            QueueKey {
+                workchain: 0,
+                lt: u64::MAX,
+                hash: HashBytes([0x55; 32]),
+            },
+            QueueKey {
+                workchain: 0,
+                lt: 1,
+                hash: HashBytes::ZERO,
+            },
+        ];
+
+        // Stored layout must be the same as the manually packed one
+        let manual = build_cell(|b| {
+            b.store_u8(-1i8 as u8)?;
+            b.store_u64(123)?;
+            b.store_u256(&HashBytes([0xaa; 32]))
+        });
+        assert_eq!(CellBuilder::build_from(keys[0])?, manual);
+
+        let mut dict = Dict::<QueueKey, u32>::new();
+        let mut unaligned = Dict::<Unaligned, u32>::new();
+        for (i, key) in keys.iter().enumerate() {
+            dict.set(key, i as u32)?;
+            unaligned.set(Unaligned(i % 2 == 0, i as u16 * 1000, true, *key), i as u32)?;
+        }
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(dict.get(key)?, Some(i as u32));
+        }
+
+        let mut sorted = keys.to_vec();
+        sorted.sort_by_key(|key| (key.workchain as u8, key.lt, key.hash));
+        let iter_keys = dict.keys().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(iter_keys, sorted);
+
+        for entry in unaligned.iter() {
+            let (key, value) = entry?;
+            let i = value as usize;
+            assert_eq!(key, Unaligned(i % 2 == 0, i as u16 * 1000, true, keys[i]));
+        }
+
+        Ok(())
+    }
 }
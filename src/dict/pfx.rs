@@ -0,0 +1,799 @@
+use crate::cell::*;
+use crate::error::Error;
+use crate::util::unlikely;
+
+use super::{join_edge, read_label, rebuild_with_child, write_label, SetMode};
+
+/// Dictionary with variable length keys which form a prefix code
+/// (where `N` is a max number of bits in each key).
+///
+/// No key in this dictionary can be a prefix of another key,
+/// so each bit string has at most one key which is its prefix.
+///
+/// # TLB scheme
+///
+/// ```text
+/// phm_edge#_ {n:#} {X:Type} {l:#} {m:#} label:(HmLabel ~l n)
+///            {n = (~m) + l} node:(PfxHashmapNode m X) = PfxHashmap n X;
+///
+/// phmn_leaf$0 {n:#} {X:Type} value:X = PfxHashmapNode n X;
+/// phmn_fork$1 {n:#} {X:Type} left:^(PfxHashmap n X)
+///             right:^(PfxHashmap n X) = PfxHashmapNode (n + 1) X;
+///
+/// phme_empty$0 {n:#} {X:Type} = PfxHashmapE n X;
+/// phme_root$1 {n:#} {X:Type} root:^(PfxHashmap n X) = PfxHashmapE n X;
+/// ```
+pub struct PfxDict<const N: u16>(pub(crate) Option<Cell>);
+
+impl<const N: u16> ExactSize for PfxDict<N> {
+    #[inline]
+    fn exact_size(&self) -> Size {
+        Size {
+            bits: 1,
+            refs: self.0.is_some() as u8,
+        }
+    }
+}
+
+impl<'a, const N: u16> Load<'a> for PfxDict<N> {
+    #[inline]
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match <_>::load_from(slice) {
+            Ok(dict) => Ok(Self(dict)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<const N: u16> Store for PfxDict<N> {
+    #[inline]
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &dyn CellContext,
+    ) -> Result<(), Error> {
+        self.0.store_into(builder, context)
+    }
+}
+
+impl<const N: u16> Default for PfxDict<N> {
+    #[inline]
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<const N: u16> Clone for PfxDict<N> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<const N: u16> Eq for PfxDict<N> {}
+impl<const N: u16> PartialEq for PfxDict<N> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(this), Some(other)) => this.as_ref() == other.as_ref(),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<const N: u16> From<Option<Cell>> for PfxDict<N> {
+    #[inline]
+    fn from(value: Option<Cell>) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: u16> std::fmt::Debug for PfxDict<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PfxDict")
+            .field("key_bit_len", &N)
+            .field("root", &self.0)
+            .finish()
+    }
+}
+
+impl<const N: u16> PfxDict<N> {
+    const _ASSERT: () = assert!(N > 0, "PfxDict with 0-bit key is invalid");
+
+    /// Creates an empty dictionary.
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Returns `true` if the dictionary contains no elements.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Returns the underlying root cell of the dictionary.
+    #[inline]
+    pub const fn root(&self) -> &Option<Cell> {
+        &self.0
+    }
+
+    /// Returns the underlying root cell of the dictionary.
+    #[inline]
+    pub fn into_root(self) -> Option<Cell> {
+        self.0
+    }
+
+    /// Returns a `CellSlice` of the value corresponding to the key.
+    ///
+    /// NOTE: Uses the default cell context.
+    pub fn get<'a>(&'a self, key: CellSlice<'_>) -> Result<Option<CellSlice<'a>>, Error> {
+        self.get_ext(key, Cell::empty_context())
+    }
+
+    /// Returns a `CellSlice` of the value corresponding to the key.
+    pub fn get_ext<'a, 'c: 'a>(
+        &'a self,
+        key: CellSlice<'_>,
+        context: &'c dyn CellContext,
+    ) -> Result<Option<CellSlice<'a>>, Error> {
+        if key.size_bits() > N {
+            return Err(Error::CellUnderflow);
+        }
+        match pfx_dict_get(self.0.as_ref(), N, key, true, context) {
+            Ok(res) => Ok(res.map(|(_, value)| value)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Finds the key which is a prefix of the specified bit string.
+    /// Returns the matched part of `key` and a `CellSlice` of the value.
+    ///
+    /// NOTE: Uses the default cell context.
+    pub fn get_prefix<'a, 'b>(
+        &'a self,
+        key: CellSlice<'b>,
+    ) -> Result<Option<(CellSlice<'b>, CellSlice<'a>)>, Error> {
+        self.get_prefix_ext(key, Cell::empty_context())
+    }
+
+    /// Finds the key which is a prefix of the specified bit string.
+    /// Returns the matched part of `key` and a `CellSlice` of the value.
+    pub fn get_prefix_ext<'a, 'b, 'c: 'a>(
+        &'a self,
+        key: CellSlice<'b>,
+        context: &'c dyn CellContext,
+    ) -> Result<Option<(CellSlice<'b>, CellSlice<'a>)>, Error> {
+        pfx_dict_get(self.0.as_ref(), N, key, false, context)
+    }
+
+    /// Returns `true` if the dictionary contains a value for the specified key.
+    pub fn contains_key(&self, key: CellSlice<'_>) -> Result<bool, Error> {
+        Ok(ok!(self.get(key)).is_some())
+    }
+
+    /// Sets the value associated with the key in the dictionary.
+    ///
+    /// Returns `false` if the key is a prefix of some existing key
+    /// or some existing key is a prefix of it.
+    pub fn set_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        value: &dyn Store,
+        context: &dyn CellContext,
+    ) -> Result<bool, Error> {
+        pfx_dict_insert(&mut self.0, key, N, value, SetMode::Set, context)
+    }
+
+    /// Sets the value associated with the key in the dictionary
+    /// only if the key was already present in it.
+    pub fn replace_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        value: &dyn Store,
+        context: &dyn CellContext,
+    ) -> Result<bool, Error> {
+        pfx_dict_insert(&mut self.0, key, N, value, SetMode::Replace, context)
+    }
+
+    /// Sets the value associated with key in dictionary,
+    /// but only if it is not already present.
+    ///
+    /// Returns `false` if the key is a prefix of some existing key
+    /// or some existing key is a prefix of it.
+    pub fn add_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        value: &dyn Store,
+        context: &dyn CellContext,
+    ) -> Result<bool, Error> {
+        pfx_dict_insert(&mut self.0, key, N, value, SetMode::Add, context)
+    }
+
+    /// Removes the value associated with key in dictionary.
+    /// Returns an optional removed value as cell slice parts.
+    pub fn remove_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        context: &dyn CellContext,
+    ) -> Result<Option<CellSliceParts>, Error> {
+        pfx_dict_remove(&mut self.0, key, N, context)
+    }
+
+    /// Gets an iterator over the entries of the dictionary, sorted by key.
+    /// The iterator element type is `Result<(CellBuilder, CellSlice)>`.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter(&'_ self) -> PfxIter<'_> {
+        PfxIter::new(&self.0, N)
+    }
+
+    /// Sets the value associated with the key in the dictionary.
+    ///
+    /// Use [`set_ext`] if you need to use a custom cell context.
+    ///
+    /// [`set_ext`]: PfxDict::set_ext
+    pub fn set<T: Store>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error> {
+        self.set_ext(key, &value, Cell::empty_context())
+    }
+
+    /// Sets the value associated with the key in the dictionary
+    /// only if the key was already present in it.
+    ///
+    /// Use [`replace_ext`] if you need to use a custom cell context.
+    ///
+    /// [`replace_ext`]: PfxDict::replace_ext
+    pub fn replace<T: Store>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error> {
+        self.replace_ext(key, &value, Cell::empty_context())
+    }
+
+    /// Sets the value associated with key in dictionary,
+    /// but only if it is not already present.
+    ///
+    /// Use [`add_ext`] if you need to use a custom cell context.
+    ///
+    /// [`add_ext`]: PfxDict::add_ext
+    pub fn add<T: Store>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error> {
+        self.add_ext(key, &value, Cell::empty_context())
+    }
+
+    /// Removes the value associated with key in dictionary.
+    /// Returns an optional removed value as cell slice parts.
+    ///
+    /// Use [`remove_ext`] if you need to use a custom cell context.
+    ///
+    /// [`remove_ext`]: PfxDict::remove_ext
+    pub fn remove(&mut self, key: CellSlice<'_>) -> Result<Option<CellSliceParts>, Error> {
+        self.remove_ext(key, Cell::empty_context())
+    }
+}
+
+/// An iterator over the entries of a [`PfxDict`].
+///
+/// This struct is created by the [`iter`] method on [`PfxDict`].
+/// See its documentation for more.
+///
+/// [`iter`]: PfxDict::iter
+#[derive(Clone)]
+pub struct PfxIter<'a> {
+    segments: Vec<PfxIterSegment<'a>>,
+    builder: Box<CellBuilder>,
+    broken: bool,
+}
+
+impl<'a> PfxIter<'a> {
+    /// Creates an iterator over the entries of a dictionary.
+    pub fn new(root: &'a Option<Cell>, bit_len: u16) -> Self {
+        let mut segments = Vec::new();
+        if let Some(root) = root {
+            segments.push(PfxIterSegment {
+                cell: root.as_ref(),
+                prefix_len: 0,
+                bit: None,
+                remaining_bit_len: bit_len,
+            });
+        }
+
+        Self {
+            segments,
+            builder: Default::default(),
+            broken: false,
+        }
+    }
+
+    fn next_impl(&mut self) -> Result<Option<(CellBuilder, CellSlice<'a>)>, Error> {
+        loop {
+            let Some(segment) = self.segments.pop() else {
+                return Ok(None);
+            };
+
+            // Restore the key prefix of this segment
+            ok!(self
+                .builder
+                .rewind(self.builder.size_bits() - segment.prefix_len));
+            if let Some(bit) = segment.bit {
+                ok!(self.builder.store_bit(bit));
+            }
+
+            let mut data = ok!(CellSlice::new(segment.cell));
+            let label = ok!(read_label(&mut data, segment.remaining_bit_len));
+            let Some(remaining_bit_len) = segment.remaining_bit_len.checked_sub(label.size_bits())
+            else {
+                return Err(Error::CellUnderflow);
+            };
+            ok!(self.builder.store_slice_data(label));
+
+            if !ok!(data.load_bit()) {
+                // Leaf
+                return Ok(Some(((*self.builder).clone(), data)));
+            }
+
+            // Fork
+            if unlikely(remaining_bit_len == 0 || data.size_refs() < 2) {
+                return Err(Error::CellUnderflow);
+            }
+
+            let prefix_len = self.builder.size_bits();
+            for bit in [true, false] {
+                self.segments.push(PfxIterSegment {
+                    cell: ok!(data.get_reference(bit as u8)),
+                    prefix_len,
+                    bit: Some(bit),
+                    remaining_bit_len: remaining_bit_len - 1,
+                });
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for PfxIter<'a> {
+    type Item = Result<(CellBuilder, CellSlice<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unlikely(self.broken) {
+            return None;
+        }
+
+        match self.next_impl() {
+            Ok(res) => res.map(Ok),
+            Err(e) => {
+                self.broken = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PfxIterSegment<'a> {
+    cell: &'a DynCell,
+    prefix_len: u16,
+    bit: Option<bool>,
+    remaining_bit_len: u16,
+}
+
+/// Searches for the value by the exact key or by the key prefix.
+fn pfx_dict_get<'a, 'b, 'c: 'a>(
+    dict: Option<&'a Cell>,
+    key_bit_len: u16,
+    key: CellSlice<'b>,
+    exact: bool,
+    context: &'c dyn CellContext,
+) -> Result<Option<(CellSlice<'b>, CellSlice<'a>)>, Error> {
+    let mut data = match dict {
+        Some(data) => ok!(context
+            .load_dyn_cell(data.as_ref(), LoadMode::Full)
+            .and_then(CellSlice::new)),
+        None => return Ok(None),
+    };
+
+    let mut remaining = key;
+    let mut key_bit_len = key_bit_len;
+    loop {
+        // Read the key part written in the current edge
+        let prefix = ok!(read_label(&mut data, key_bit_len));
+        let Some(remaining_bit_len) = key_bit_len.checked_sub(prefix.size_bits()) else {
+            return Err(Error::CellUnderflow);
+        };
+
+        // Remove this prefix from the key
+        remaining = match remaining.strip_data_prefix(&prefix) {
+            Some(stripped_key) => stripped_key,
+            None => return Ok(None),
+        };
+
+        if !ok!(data.load_bit()) {
+            // Reached leaf
+            return Ok(if !exact || remaining.is_data_empty() {
+                let matched = key.get_prefix(key.size_bits() - remaining.size_bits(), 0);
+                Some((matched, data))
+            } else {
+                None
+            });
+        }
+
+        // Reached fork
+        if unlikely(remaining_bit_len == 0) {
+            return Err(Error::CellUnderflow);
+        } else if remaining.is_data_empty() {
+            // The key is a prefix of some existing keys
+            return Ok(None);
+        }
+
+        // Load next child based on the next bit
+        let child_index = ok!(remaining.load_bit()) as u8;
+        data = match data.cell().reference(child_index) {
+            Some(cell) => ok!(context
+                .load_dyn_cell(cell, LoadMode::Full)
+                .and_then(CellSlice::new)),
+            None => return Err(Error::CellUnderflow),
+        };
+        key_bit_len = remaining_bit_len - 1;
+    }
+}
+
+fn pfx_dict_insert(
+    dict: &mut Option<Cell>,
+    key: CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    mode: SetMode,
+    context: &dyn CellContext,
+) -> Result<bool, Error> {
+    if key.size_bits() > key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let root = match dict {
+        Some(root) => match ok!(pfx_insert_impl(
+            root.as_ref(),
+            key,
+            key_bit_len,
+            value,
+            mode,
+            context
+        )) {
+            Some(root) => root,
+            None => return Ok(false),
+        },
+        None if mode.can_add() => ok!(make_pfx_leaf(&key, key_bit_len, value, context)),
+        None => return Ok(false),
+    };
+
+    *dict = Some(root);
+    Ok(true)
+}
+
+/// Returns a rebuilt node or `None` if the dictionary was not changed.
+fn pfx_insert_impl(
+    node: &DynCell,
+    mut key: CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    mode: SetMode,
+    context: &dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    let node = ok!(context.load_dyn_cell(node, LoadMode::Full));
+    let mut data = ok!(CellSlice::new(node));
+
+    let prefix = ok!(read_label(&mut data, key_bit_len));
+    let Some(remaining_bit_len) = key_bit_len.checked_sub(prefix.size_bits()) else {
+        return Err(Error::CellUnderflow);
+    };
+
+    let lcp = key.longest_common_data_prefix(&prefix);
+    if lcp.size_bits() < prefix.size_bits() {
+        // The key is a prefix of some existing keys
+        if lcp.size_bits() == key.size_bits() || !mode.can_add() {
+            return Ok(None);
+        }
+
+        // Split the edge
+        let child_key_bit_len = key_bit_len - lcp.size_bits() - 1;
+
+        let mut old_rem = prefix;
+        ok!(old_rem.skip_first(lcp.size_bits(), 0));
+        let old_to_right = ok!(old_rem.load_bit());
+        ok!(key.skip_first(lcp.size_bits() + 1, 0));
+
+        let mut left = {
+            let mut builder = CellBuilder::new();
+            ok!(write_label(&old_rem, child_key_bit_len, &mut builder));
+            ok!(builder.store_slice(data));
+            ok!(builder.build_ext(context))
+        };
+        let mut right = ok!(make_pfx_leaf(&key, child_key_bit_len, value, context));
+        if old_to_right {
+            std::mem::swap(&mut left, &mut right);
+        }
+
+        let mut builder = CellBuilder::new();
+        ok!(write_label(&lcp, key_bit_len, &mut builder));
+        ok!(builder.store_bit_one());
+        ok!(builder.store_reference(left));
+        ok!(builder.store_reference(right));
+        return builder.build_ext(context).map(Some);
+    }
+
+    ok!(key.skip_first(prefix.size_bits(), 0));
+
+    if !ok!(data.load_bit()) {
+        // Some existing key is a prefix of the key
+        if !key.is_data_empty() || !mode.can_replace() {
+            return Ok(None);
+        }
+
+        // Replace the value of the leaf
+        let label_bits = node.bit_len() - data.size_bits() - 1;
+        let mut builder = CellBuilder::new();
+        ok!(builder.store_slice_data(node.as_slice_allow_pruned().get_prefix(label_bits, 0)));
+        ok!(builder.store_bit_zero());
+        ok!(value.store_into(&mut builder, context));
+        return builder.build_ext(context).map(Some);
+    }
+
+    if unlikely(remaining_bit_len == 0 || data.size_refs() < 2) {
+        return Err(Error::CellUnderflow);
+    } else if key.is_data_empty() {
+        // The key is a prefix of some existing keys
+        return Ok(None);
+    }
+
+    let index = ok!(key.load_bit()) as u8;
+    let child = ok!(data.get_reference(index));
+    match ok!(pfx_insert_impl(
+        child,
+        key,
+        remaining_bit_len - 1,
+        value,
+        mode,
+        context
+    )) {
+        Some(child) => rebuild_with_child(node, index, child, context).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn pfx_dict_remove(
+    dict: &mut Option<Cell>,
+    key: CellSlice<'_>,
+    key_bit_len: u16,
+    context: &dyn CellContext,
+) -> Result<Option<CellSliceParts>, Error> {
+    if key.size_bits() > key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let Some(root) = dict else {
+        return Ok(None);
+    };
+
+    match ok!(pfx_remove_impl(root.clone(), key, key_bit_len, context)) {
+        Some((root, removed)) => {
+            *dict = root;
+            Ok(Some(removed))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns a rebuilt node (or `None` if it was removed) and the removed value.
+#[allow(clippy::type_complexity)]
+fn pfx_remove_impl(
+    node: Cell,
+    mut key: CellSlice<'_>,
+    key_bit_len: u16,
+    context: &dyn CellContext,
+) -> Result<Option<(Option<Cell>, CellSliceParts)>, Error> {
+    let node = ok!(context.load_cell(node, LoadMode::Full));
+    let mut data = ok!(node.as_slice());
+
+    let prefix = ok!(read_label(&mut data, key_bit_len));
+    let Some(remaining_bit_len) = key_bit_len.checked_sub(prefix.size_bits()) else {
+        return Err(Error::CellUnderflow);
+    };
+
+    key = match key.strip_data_prefix(&prefix) {
+        Some(stripped_key) => stripped_key,
+        None => return Ok(None),
+    };
+
+    if !ok!(data.load_bit()) {
+        // Remove the leaf if the key matches
+        return Ok(if key.is_data_empty() {
+            let range = data.range();
+            Some((None, (node, range)))
+        } else {
+            None
+        });
+    }
+
+    if unlikely(remaining_bit_len == 0 || data.size_refs() < 2) {
+        return Err(Error::CellUnderflow);
+    } else if key.is_data_empty() {
+        return Ok(None);
+    }
+
+    let index = ok!(key.load_bit()) as u8;
+    let child = ok!(data.get_reference_cloned(index));
+    let Some((child, removed)) = ok!(pfx_remove_impl(child, key, remaining_bit_len - 1, context))
+    else {
+        return Ok(None);
+    };
+
+    let node = match child {
+        Some(child) => ok!(rebuild_with_child(node.as_ref(), index, child, context)),
+        None => {
+            // Collapse fork with the remaining child
+            let opposite = ok!(data.get_reference(1 - index));
+            ok!(join_edge(
+                &prefix,
+                index == 0,
+                opposite,
+                key_bit_len,
+                context
+            ))
+        }
+    };
+    Ok(Some((Some(node), removed)))
+}
+
+fn make_pfx_leaf(
+    key: &CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    context: &dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(key, key_bit_len, &mut builder));
+    ok!(builder.store_bit_zero());
+    ok!(value.store_into(&mut builder, context));
+    builder.build_ext(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boc::BocRepr;
+
+    fn build_key(bits: &str) -> CellBuilder {
+        let mut builder = CellBuilder::new();
+        for bit in bits.chars() {
+            builder.store_bit(bit == '1').unwrap();
+        }
+        builder
+    }
+
+    fn key_to_string(key: &CellBuilder) -> String {
+        let slice = key.as_data_slice();
+        (0..slice.size_bits())
+            .map(|i| if slice.get_bit(i).unwrap() { '1' } else { '0' })
+            .collect()
+    }
+
+    #[test]
+    fn pfx_dict_set_get() -> anyhow::Result<()> {
+        let mut dict = PfxDict::<32>::new();
+        assert!(dict.set(build_key("0").as_data_slice(), 0u32)?);
+        assert!(dict.set(build_key("10").as_data_slice(), 1u32)?);
+        assert!(dict.set(build_key("1100").as_data_slice(), 2u32)?);
+        assert!(dict.set(build_key("1101").as_data_slice(), 3u32)?);
+        assert!(dict.set(build_key("111").as_data_slice(), 4u32)?);
+
+        // Not a prefix code
+        assert!(!dict.set(build_key("1").as_data_slice(), 5u32)?);
+        assert!(!dict.set(build_key("01").as_data_slice(), 5u32)?);
+        assert!(!dict.set(build_key("110").as_data_slice(), 5u32)?);
+        assert!(!dict.add(build_key("10").as_data_slice(), 5u32)?);
+        assert!(!dict.replace(build_key("1110").as_data_slice(), 5u32)?);
+
+        for (key, value) in [("0", 0), ("10", 1), ("1100", 2), ("1101", 3), ("111", 4)] {
+            let mut slice = dict.get(build_key(key).as_data_slice())?.unwrap();
+            assert_eq!(slice.load_u32()?, value);
+        }
+        for key in ["", "1", "11", "110", "1110", "011"] {
+            assert!(!dict.contains_key(build_key(key).as_data_slice())?);
+        }
+
+        assert!(dict.replace(build_key("10").as_data_slice(), 10u32)?);
+        let mut slice = dict.get(build_key("10").as_data_slice())?.unwrap();
+        assert_eq!(slice.load_u32()?, 10);
+
+        // Too long key
+        assert!(dict.get(CellBuilder::new().as_data_slice()).is_ok());
+        let mut long_key = CellBuilder::new();
+        long_key.store_zeros(33)?;
+        assert!(dict.get(long_key.as_data_slice()).is_err());
+        assert!(dict.set(long_key.as_data_slice(), 0u32).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfx_dict_get_prefix() -> anyhow::Result<()> {
+        let mut dict = PfxDict::<16>::new();
+        dict.set(build_key("0").as_data_slice(), 0u8)?;
+        dict.set(build_key("101").as_data_slice(), 1u8)?;
+        dict.set(build_key("1100").as_data_slice(), 2u8)?;
+
+        for (key, matched, value) in [
+            ("0", "0", 0),
+            ("0111111111", "0", 0),
+            ("101", "101", 1),
+            ("1010101010101010101", "101", 1),
+            ("11001", "1100", 2),
+        ] {
+            let key = build_key(key);
+            let (prefix, mut slice) = dict.get_prefix(key.as_data_slice())?.unwrap();
+            assert!(prefix.contents_eq(&build_key(matched).as_data_slice())?);
+            assert_eq!(slice.load_u8()?, value);
+        }
+
+        for key in ["", "1", "10", "100", "111", "11010"] {
+            assert!(dict.get_prefix(build_key(key).as_data_slice())?.is_none());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfx_dict_remove() -> anyhow::Result<()> {
+        let keys = ["000", "001", "01", "1000000", "1000001", "101", "11"];
+
+        let mut dict = PfxDict::<8>::new();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(dict.set(build_key(key).as_data_slice(), i as u16)?);
+        }
+
+        assert!(dict.remove(build_key("1").as_data_slice())?.is_none());
+        assert!(dict.remove(build_key("0011").as_data_slice())?.is_none());
+
+        for (i, key) in keys.iter().enumerate().rev() {
+            let (cell, range) = dict.remove(build_key(key).as_data_slice())?.unwrap();
+            assert_eq!(range.apply(&cell)?.load_u16()?, i as u16);
+            assert!(!dict.contains_key(build_key(key).as_data_slice())?);
+
+            // Removal must produce the same tree as building without the key
+            let mut expected = PfxDict::<8>::new();
+            for (j, key) in keys[..i].iter().enumerate() {
+                expected.set(build_key(key).as_data_slice(), j as u16)?;
+            }
+            assert_eq!(dict, expected);
+        }
+        assert!(dict.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfx_dict_iter_and_serde() -> anyhow::Result<()> {
+        let keys = ["1111", "0", "1110", "10", "110"];
+
+        let mut dict = PfxDict::<4>::new();
+        for (i, key) in keys.iter().enumerate() {
+            dict.set(build_key(key).as_data_slice(), i as u8)?;
+        }
+
+        let encoded = BocRepr::encode_base64(&dict)?;
+        let decoded = BocRepr::decode_base64::<PfxDict<4>, _>(encoded)?;
+        assert_eq!(decoded, dict);
+
+        let entries = decoded
+            .iter()
+            .map(|entry| {
+                let (key, mut value) = entry?;
+                Ok((key_to_string(&key), value.load_u8()?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        assert_eq!(
+            entries,
+            [
+                ("0".to_owned(), 1),
+                ("10".to_owned(), 3),
+                ("110".to_owned(), 4),
+                ("1110".to_owned(), 2),
+                ("1111".to_owned(), 0),
+            ]
+        );
+
+        Ok(())
+    }
+}
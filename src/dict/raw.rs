@@ -675,6 +675,12 @@ impl<'a> RawOwnedIter<'a> {
     pub fn is_signed(&self) -> bool {
         self.inner.signed
     }
+
+    #[allow(unused)]
+    #[inline]
+    pub(crate) fn finish(&mut self, err: Error) -> Error {
+        self.inner.finish(err)
+    }
 }
 
 impl Iterator for RawOwnedIter<'_> {
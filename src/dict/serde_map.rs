@@ -0,0 +1,185 @@
+//! Human-readable serde representation of [`Dict`] as a map.
+//!
+//! Keys are serialized as strings using their [`Display`] and [`FromStr`]
+//! implementations, values are serialized as is. Non-human-readable formats
+//! still use a BOC representation.
+//!
+//! # Example
+//!
+//! ```
+//! # use everscale_types::dict::Dict;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Balances {
+//!     #[serde(with = "everscale_types::dict::serde_map")]
+//!     items: Dict<u32, u64>,
+//! }
+//! ```
+//!
+//! [`Display`]: std::fmt::Display
+//! [`FromStr`]: std::str::FromStr
+
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cell::{Load, Store};
+use crate::dict::{Dict, DictKey};
+
+/// Serializes the dictionary as a map with string keys.
+pub fn serialize<K, V, S>(dict: &Dict<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Display + Store + DictKey,
+    for<'a> V: Serialize + Load<'a>,
+    S: Serializer,
+{
+    use serde::ser::Error;
+
+    struct DisplayKey<'a, K>(&'a K);
+
+    impl<K: Display> Serialize for DisplayKey<'_, K> {
+        #[inline]
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self.0)
+        }
+    }
+
+    if serializer.is_human_readable() {
+        let mut map = ok!(serializer.serialize_map(None));
+        for entry in dict.iter() {
+            match entry {
+                Ok((key, value)) => ok!(map.serialize_entry(&DisplayKey(&key), &value)),
+                Err(e) => return Err(Error::custom(e)),
+            }
+        }
+        map.end()
+    } else {
+        crate::boc::BocRepr::serialize(dict, serializer)
+    }
+}
+
+/// Deserializes the dictionary from a map with string keys.
+pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<Dict<K, V>, D::Error>
+where
+    K: FromStr + Store + DictKey,
+    K::Err: Display,
+    V: Deserialize<'de> + Store,
+    D: Deserializer<'de>,
+{
+    struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+    impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+    where
+        K: FromStr + Store + DictKey,
+        K::Err: Display,
+        V: Deserialize<'de> + Store,
+    {
+        type Value = Dict<K, V>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map with string keys")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            use serde::de::Error;
+
+            // NOTE: entries are inserted one by one since the order of the parsed keys
+            // may differ from the bit order of the stored keys (e.g. for signed integers)
+            let mut result = Dict::new();
+            while let Some(key) = ok!(map.next_key::<String>()) {
+                let parsed = ok!(K::from_str(&key).map_err(Error::custom));
+                let value = ok!(map.next_value::<V>());
+                if !ok!(result.add(parsed, value).map_err(Error::custom)) {
+                    return Err(Error::custom(format_args!("duplicate key: {key}")));
+                }
+            }
+            Ok(result)
+        }
+    }
+
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_map(MapVisitor::<K, V>(PhantomData))
+    } else {
+        crate::boc::BocRepr::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::HashBytes;
+
+    #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(with = "crate::dict::serde_map")]
+        items: Dict<u32, u64>,
+        #[serde(with = "crate::dict::serde_map")]
+        hashes: Dict<HashBytes, bool>,
+    }
+
+    #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+    struct SignedWrapper {
+        #[serde(with = "crate::dict::serde_map")]
+        items: Dict<i32, u8>,
+    }
+
+    #[test]
+    fn serde_map_round_trip() -> anyhow::Result<()> {
+        let mut items = Dict::<u32, u64>::new();
+        items.set(10, 100)?;
+        items.set(2, 20)?;
+        items.set(u32::MAX, 0)?;
+
+        let mut hashes = Dict::<HashBytes, bool>::new();
+        hashes.set(HashBytes([0x11; 32]), true)?;
+
+        let wrapper = Wrapper { items, hashes };
+
+        let json = serde_json::to_value(&wrapper)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "items": { "2": 20, "10": 100, "4294967295": 0 },
+                "hashes": {
+                    "1111111111111111111111111111111111111111111111111111111111111111": true
+                },
+            })
+        );
+
+        let parsed = serde_json::from_value::<Wrapper>(json)?;
+        assert_eq!(parsed, wrapper);
+
+        let empty = serde_json::from_str::<Wrapper>(r#"{"items":{},"hashes":{}}"#)?;
+        assert!(empty.items.is_empty() && empty.hashes.is_empty());
+
+        assert!(serde_json::from_str::<Wrapper>(r#"{"items":{"a":1},"hashes":{}}"#).is_err());
+        assert!(serde_json::from_str::<Wrapper>(r#"{"items":{"1":1,"1":2},"hashes":{}}"#).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn serde_map_signed_keys() -> anyhow::Result<()> {
+        let mut items = Dict::<i32, u8>::new();
+        for (i, key) in [-1, 0, i32::MIN, 5, -100, i32::MAX].into_iter().enumerate() {
+            items.set(key, i as u8)?;
+        }
+        let wrapper = SignedWrapper { items };
+
+        let json = serde_json::to_string(&wrapper)?;
+        let parsed = serde_json::from_str::<SignedWrapper>(&json)?;
+        assert_eq!(parsed, wrapper);
+        assert_eq!(parsed.items.get(-100)?, Some(4));
+
+        // Key order in the input doesn't matter
+        let parsed = serde_json::from_str::<SignedWrapper>(
+            r#"{"items":{"2147483647":5,"-100":4,"5":3,"-2147483648":2,"0":1,"-1":0}}"#,
+        )?;
+        assert_eq!(parsed, wrapper);
+
+        Ok(())
+    }
+}
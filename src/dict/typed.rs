@@ -5,6 +5,7 @@ use std::marker::PhantomData;
 use crate::cell::*;
 use crate::dict::dict_remove_owned;
 use crate::error::Error;
+use crate::merkle::MerkleProof;
 use crate::util::*;
 
 use super::{
@@ -884,6 +885,52 @@ where
     }
 }
 
+impl<K, V> Dict<K, V>
+where
+    K: Store + DictKey,
+    for<'a> V: Load<'a>,
+{
+    /// Creates a Merkle proof for the path to the specified key.
+    ///
+    /// The proof root is the dictionary root cell. It contains all cells
+    /// visited during the lookup (including the value), so it can be used
+    /// to prove both the presence and the absence of the key.
+    ///
+    /// Returns [`Error::EmptyProof`] if the dictionary is empty.
+    pub fn prove<Q>(&self, key: Q) -> Result<MerkleProof, Error>
+    where
+        Q: Borrow<K>,
+    {
+        let Some(root) = &self.root else {
+            return Err(Error::EmptyProof);
+        };
+
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = Dict::<K, V>::from_raw(Some(usage_tree.track(root)));
+        ok!(tracked.get(key));
+
+        MerkleProof::create(root.as_ref(), usage_tree).build()
+    }
+
+    /// Checks the Merkle proof created by [`Dict::prove`] and returns
+    /// the value corresponding to the key.
+    ///
+    /// Returns `Ok(None)` if the proof shows that the key is absent.
+    /// Fails with [`Error::InvalidData`] if the proof is for a different root
+    /// and with [`Error::PrunedBranchAccess`] if it doesn't cover the key.
+    pub fn verify_get<Q>(
+        proof: &MerkleProof,
+        root_hash: &HashBytes,
+        key: Q,
+    ) -> Result<Option<V>, Error>
+    where
+        Q: Borrow<K>,
+    {
+        let root = ok!(proof.verify(root_hash));
+        Dict::<K, V>::from_raw(Some(root)).get(key)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<K, V> serde::Serialize for Dict<K, V>
 where
@@ -1663,6 +1710,52 @@ mod tests {
         test_big_dict(&values);
     }
 
+    #[test]
+    fn dict_prove_and_verify() -> anyhow::Result<()> {
+        let mut dict = Dict::<u32, u64>::new();
+        for i in 0..100 {
+            dict.set(i * 2, i as u64 * 1000)?;
+        }
+        let root_hash = *dict.root().as_ref().unwrap().repr_hash();
+
+        // Inclusion
+        let proof = dict.prove(42)?;
+        assert_eq!(proof.hash, root_hash);
+        assert_eq!(
+            Dict::<u32, u64>::verify_get(&proof, &root_hash, 42)?,
+            Some(21000)
+        );
+
+        // Proof doesn't cover other keys
+        assert_eq!(
+            Dict::<u32, u64>::verify_get(&proof, &root_hash, 100),
+            Err(Error::PrunedBranchAccess)
+        );
+
+        // Exclusion
+        for key in [43, 1000, u32::MAX] {
+            let proof = dict.prove(key)?;
+            assert_eq!(Dict::<u32, u64>::verify_get(&proof, &root_hash, key)?, None);
+        }
+
+        // Proof for a different root
+        let mut other = dict.clone();
+        other.set(42, 0)?;
+        let other_proof = other.prove(42)?;
+        assert_eq!(
+            Dict::<u32, u64>::verify_get(&other_proof, &root_hash, 42),
+            Err(Error::InvalidData)
+        );
+
+        // Empty dict
+        assert_eq!(
+            Dict::<u32, u64>::new().prove(42).unwrap_err(),
+            Error::EmptyProof
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn dict_iter_union() -> anyhow::Result<()> {
         let mut left = Dict::<i32, i32>::new();
@@ -0,0 +1,999 @@
+use crate::cell::*;
+use crate::error::Error;
+use crate::util::unlikely;
+
+use super::{join_edge, read_label, rebuild_with_child, write_label, SetMode};
+
+/// Dictionary with variable length keys (where `N` is a max number of bits in each key).
+///
+/// Unlike [`PfxDict`], keys can be prefixes of each other.
+///
+/// [`PfxDict`]: crate::dict::PfxDict
+///
+/// # TLB scheme
+///
+/// ```text
+/// vhm_edge#_ {n:#} {X:Type} {l:#} {m:#} label:(HmLabel ~l n)
+///            {n = (~m) + l} node:(VarHashmapNode m X) = VarHashmap n X;
+///
+/// vhmn_leaf$00 {n:#} {X:Type} value:X = VarHashmapNode n X;
+/// vhmn_fork$01 {n:#} {X:Type} left:^(VarHashmap n X)
+///              right:^(VarHashmap n X) value:(Maybe X) = VarHashmapNode (n + 1) X;
+/// vhmn_cont$1 {n:#} {X:Type} branch:Bit child:^(VarHashmap n X)
+///             value:X = VarHashmapNode (n + 1) X;
+///
+/// vhme_empty$0 {n:#} {X:Type} = VarHashmapE n X;
+/// vhme_root$1 {n:#} {X:Type} root:^(VarHashmap n X) = VarHashmapE n X;
+/// ```
+pub struct VarDict<const N: u16>(pub(crate) Option<Cell>);
+
+impl<const N: u16> ExactSize for VarDict<N> {
+    #[inline]
+    fn exact_size(&self) -> Size {
+        Size {
+            bits: 1,
+            refs: self.0.is_some() as u8,
+        }
+    }
+}
+
+impl<'a, const N: u16> Load<'a> for VarDict<N> {
+    #[inline]
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match <_>::load_from(slice) {
+            Ok(dict) => Ok(Self(dict)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<const N: u16> Store for VarDict<N> {
+    #[inline]
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &dyn CellContext,
+    ) -> Result<(), Error> {
+        self.0.store_into(builder, context)
+    }
+}
+
+impl<const N: u16> Default for VarDict<N> {
+    #[inline]
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<const N: u16> Clone for VarDict<N> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<const N: u16> Eq for VarDict<N> {}
+impl<const N: u16> PartialEq for VarDict<N> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(this), Some(other)) => this.as_ref() == other.as_ref(),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<const N: u16> From<Option<Cell>> for VarDict<N> {
+    #[inline]
+    fn from(value: Option<Cell>) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: u16> std::fmt::Debug for VarDict<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VarDict")
+            .field("key_bit_len", &N)
+            .field("root", &self.0)
+            .finish()
+    }
+}
+
+impl<const N: u16> VarDict<N> {
+    /// Creates an empty dictionary.
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /// Returns `true` if the dictionary contains no elements.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Returns the underlying root cell of the dictionary.
+    #[inline]
+    pub const fn root(&self) -> &Option<Cell> {
+        &self.0
+    }
+
+    /// Returns the underlying root cell of the dictionary.
+    #[inline]
+    pub fn into_root(self) -> Option<Cell> {
+        self.0
+    }
+
+    /// Returns a `CellSlice` of the value corresponding to the key.
+    ///
+    /// NOTE: Uses the default cell context.
+    pub fn get<'a>(&'a self, key: CellSlice<'_>) -> Result<Option<CellSlice<'a>>, Error> {
+        var_dict_get(self.0.as_ref(), N, key, Cell::empty_context())
+    }
+
+    /// Returns a `CellSlice` of the value corresponding to the key.
+    pub fn get_ext<'a, 'c: 'a>(
+        &'a self,
+        key: CellSlice<'_>,
+        context: &'c dyn CellContext,
+    ) -> Result<Option<CellSlice<'a>>, Error> {
+        var_dict_get(self.0.as_ref(), N, key, context)
+    }
+
+    /// Returns `true` if the dictionary contains a value for the specified key.
+    pub fn contains_key(&self, key: CellSlice<'_>) -> Result<bool, Error> {
+        Ok(ok!(self.get(key)).is_some())
+    }
+
+    /// Sets the value associated with the key in the dictionary.
+    pub fn set_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        value: &dyn Store,
+        context: &dyn CellContext,
+    ) -> Result<bool, Error> {
+        var_dict_insert(&mut self.0, key, N, value, SetMode::Set, context)
+    }
+
+    /// Sets the value associated with the key in the dictionary
+    /// only if the key was already present in it.
+    pub fn replace_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        value: &dyn Store,
+        context: &dyn CellContext,
+    ) -> Result<bool, Error> {
+        var_dict_insert(&mut self.0, key, N, value, SetMode::Replace, context)
+    }
+
+    /// Sets the value associated with key in dictionary,
+    /// but only if it is not already present.
+    pub fn add_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        value: &dyn Store,
+        context: &dyn CellContext,
+    ) -> Result<bool, Error> {
+        var_dict_insert(&mut self.0, key, N, value, SetMode::Add, context)
+    }
+
+    /// Removes the value associated with key in dictionary.
+    /// Returns an optional removed value as cell slice parts.
+    pub fn remove_ext(
+        &mut self,
+        key: CellSlice<'_>,
+        context: &dyn CellContext,
+    ) -> Result<Option<CellSliceParts>, Error> {
+        var_dict_remove(&mut self.0, key, N, context)
+    }
+
+    /// Gets an iterator over the entries of the dictionary, sorted by key.
+    /// Each key goes before all keys which it is a prefix of.
+    /// The iterator element type is `Result<(CellBuilder, CellSlice)>`.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter(&'_ self) -> VarIter<'_> {
+        VarIter::new(&self.0, N)
+    }
+
+    /// Sets the value associated with the key in the dictionary.
+    ///
+    /// Use [`set_ext`] if you need to use a custom cell context.
+    ///
+    /// [`set_ext`]: VarDict::set_ext
+    pub fn set<T: Store>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error> {
+        self.set_ext(key, &value, Cell::empty_context())
+    }
+
+    /// Sets the value associated with the key in the dictionary
+    /// only if the key was already present in it.
+    ///
+    /// Use [`replace_ext`] if you need to use a custom cell context.
+    ///
+    /// [`replace_ext`]: VarDict::replace_ext
+    pub fn replace<T: Store>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error> {
+        self.replace_ext(key, &value, Cell::empty_context())
+    }
+
+    /// Sets the value associated with key in dictionary,
+    /// but only if it is not already present.
+    ///
+    /// Use [`add_ext`] if you need to use a custom cell context.
+    ///
+    /// [`add_ext`]: VarDict::add_ext
+    pub fn add<T: Store>(&mut self, key: CellSlice<'_>, value: T) -> Result<bool, Error> {
+        self.add_ext(key, &value, Cell::empty_context())
+    }
+
+    /// Removes the value associated with key in dictionary.
+    /// Returns an optional removed value as cell slice parts.
+    ///
+    /// Use [`remove_ext`] if you need to use a custom cell context.
+    ///
+    /// [`remove_ext`]: VarDict::remove_ext
+    pub fn remove(&mut self, key: CellSlice<'_>) -> Result<Option<CellSliceParts>, Error> {
+        self.remove_ext(key, Cell::empty_context())
+    }
+}
+
+/// An iterator over the entries of a [`VarDict`].
+///
+/// This struct is created by the [`iter`] method on [`VarDict`].
+/// See its documentation for more.
+///
+/// [`iter`]: VarDict::iter
+#[derive(Clone)]
+pub struct VarIter<'a> {
+    segments: Vec<VarIterSegment<'a>>,
+    builder: Box<CellBuilder>,
+    broken: bool,
+}
+
+impl<'a> VarIter<'a> {
+    /// Creates an iterator over the entries of a dictionary.
+    pub fn new(root: &'a Option<Cell>, bit_len: u16) -> Self {
+        let mut segments = Vec::new();
+        if let Some(root) = root {
+            segments.push(VarIterSegment {
+                cell: root.as_ref(),
+                prefix_len: 0,
+                bit: None,
+                remaining_bit_len: bit_len,
+            });
+        }
+
+        Self {
+            segments,
+            builder: Default::default(),
+            broken: false,
+        }
+    }
+
+    fn next_impl(&mut self) -> Result<Option<(CellBuilder, CellSlice<'a>)>, Error> {
+        loop {
+            let Some(segment) = self.segments.pop() else {
+                return Ok(None);
+            };
+
+            // Restore the key prefix of this segment
+            ok!(self
+                .builder
+                .rewind(self.builder.size_bits() - segment.prefix_len));
+            if let Some(bit) = segment.bit {
+                ok!(self.builder.store_bit(bit));
+            }
+
+            let mut data = ok!(CellSlice::new(segment.cell));
+            let label = ok!(read_label(&mut data, segment.remaining_bit_len));
+            let Some(remaining_bit_len) = segment.remaining_bit_len.checked_sub(label.size_bits())
+            else {
+                return Err(Error::CellUnderflow);
+            };
+            ok!(self.builder.store_slice_data(label));
+
+            let node = ok!(read_node(&mut data, remaining_bit_len));
+
+            let prefix_len = self.builder.size_bits();
+            let mut push_child = |index: u8, bit: bool| {
+                let Some(cell) = segment.cell.reference(index) else {
+                    return Err(Error::CellUnderflow);
+                };
+                self.segments.push(VarIterSegment {
+                    cell,
+                    prefix_len,
+                    bit: Some(bit),
+                    remaining_bit_len: remaining_bit_len - 1,
+                });
+                Ok(())
+            };
+
+            let value = match node {
+                VarNode::Leaf { value } => Some(value),
+                VarNode::Fork { value } => {
+                    ok!(push_child(1, true));
+                    ok!(push_child(0, false));
+                    value
+                }
+                VarNode::Cont { branch, value } => {
+                    ok!(push_child(0, branch));
+                    Some(value)
+                }
+            };
+
+            if let Some(value) = value {
+                return Ok(Some(((*self.builder).clone(), value)));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for VarIter<'a> {
+    type Item = Result<(CellBuilder, CellSlice<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unlikely(self.broken) {
+            return None;
+        }
+
+        match self.next_impl() {
+            Ok(res) => res.map(Ok),
+            Err(e) => {
+                self.broken = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct VarIterSegment<'a> {
+    cell: &'a DynCell,
+    prefix_len: u16,
+    bit: Option<bool>,
+    remaining_bit_len: u16,
+}
+
+/// Parsed `VarHashmapNode`.
+enum VarNode<'a> {
+    /// Node without children.
+    Leaf { value: CellSlice<'a> },
+    /// Node with two children (references 0 and 1) and an optional value.
+    Fork { value: Option<CellSlice<'a>> },
+    /// Node with a single child (reference 0) and a value.
+    Cont { branch: bool, value: CellSlice<'a> },
+}
+
+/// Reads the node which follows the edge label.
+fn read_node<'a>(data: &mut CellSlice<'a>, remaining_bit_len: u16) -> Result<VarNode<'a>, Error> {
+    if !ok!(data.load_bit()) {
+        if !ok!(data.load_bit()) {
+            return Ok(VarNode::Leaf { value: *data });
+        }
+
+        if unlikely(remaining_bit_len == 0) {
+            return Err(Error::CellUnderflow);
+        }
+        ok!(data.skip_first(0, 2));
+
+        let value = if ok!(data.load_bit()) {
+            Some(*data)
+        } else {
+            None
+        };
+        Ok(VarNode::Fork { value })
+    } else {
+        if unlikely(remaining_bit_len == 0) {
+            return Err(Error::CellUnderflow);
+        }
+        let branch = ok!(data.load_bit());
+        ok!(data.skip_first(0, 1));
+
+        Ok(VarNode::Cont {
+            branch,
+            value: *data,
+        })
+    }
+}
+
+fn var_dict_get<'a, 'c: 'a>(
+    dict: Option<&'a Cell>,
+    key_bit_len: u16,
+    mut key: CellSlice<'_>,
+    context: &'c dyn CellContext,
+) -> Result<Option<CellSlice<'a>>, Error> {
+    if key.size_bits() > key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let mut data = match dict {
+        Some(data) => ok!(context
+            .load_dyn_cell(data.as_ref(), LoadMode::Full)
+            .and_then(CellSlice::new)),
+        None => return Ok(None),
+    };
+
+    let mut key_bit_len = key_bit_len;
+    loop {
+        // Read the key part written in the current edge
+        let prefix = ok!(read_label(&mut data, key_bit_len));
+        let Some(remaining_bit_len) = key_bit_len.checked_sub(prefix.size_bits()) else {
+            return Err(Error::CellUnderflow);
+        };
+
+        // Remove this prefix from the key
+        key = match key.strip_data_prefix(&prefix) {
+            Some(stripped_key) => stripped_key,
+            None => return Ok(None),
+        };
+
+        let cell = data.cell();
+        let child_index = match ok!(read_node(&mut data, remaining_bit_len)) {
+            VarNode::Leaf { value } => {
+                return Ok(key.is_data_empty().then_some(value));
+            }
+            VarNode::Fork { value } => {
+                if key.is_data_empty() {
+                    return Ok(value);
+                }
+                ok!(key.load_bit()) as u8
+            }
+            VarNode::Cont { branch, value } => {
+                if key.is_data_empty() {
+                    return Ok(Some(value));
+                } else if ok!(key.load_bit()) != branch {
+                    return Ok(None);
+                }
+                0
+            }
+        };
+
+        // Load next child
+        data = match cell.reference(child_index) {
+            Some(cell) => ok!(context
+                .load_dyn_cell(cell, LoadMode::Full)
+                .and_then(CellSlice::new)),
+            None => return Err(Error::CellUnderflow),
+        };
+        key_bit_len = remaining_bit_len - 1;
+    }
+}
+
+fn var_dict_insert(
+    dict: &mut Option<Cell>,
+    key: CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    mode: SetMode,
+    context: &dyn CellContext,
+) -> Result<bool, Error> {
+    if key.size_bits() > key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let root = match dict {
+        Some(root) => match ok!(var_insert_impl(
+            root.as_ref(),
+            key,
+            key_bit_len,
+            value,
+            mode,
+            context
+        )) {
+            Some(root) => root,
+            None => return Ok(false),
+        },
+        None if mode.can_add() => ok!(make_var_leaf(&key, key_bit_len, value, context)),
+        None => return Ok(false),
+    };
+
+    *dict = Some(root);
+    Ok(true)
+}
+
+/// Returns a rebuilt node or `None` if the dictionary was not changed.
+fn var_insert_impl(
+    node: &DynCell,
+    mut key: CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    mode: SetMode,
+    context: &dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    let node = ok!(context.load_dyn_cell(node, LoadMode::Full));
+    let mut data = ok!(CellSlice::new(node));
+
+    let prefix = ok!(read_label(&mut data, key_bit_len));
+    let Some(remaining_bit_len) = key_bit_len.checked_sub(prefix.size_bits()) else {
+        return Err(Error::CellUnderflow);
+    };
+    let raw_label = node
+        .as_slice_allow_pruned()
+        .get_prefix(node.bit_len() - data.size_bits(), 0);
+
+    let lcp = key.longest_common_data_prefix(&prefix);
+    if lcp.size_bits() < prefix.size_bits() {
+        if !mode.can_add() {
+            return Ok(None);
+        }
+
+        // Split the edge
+        let child_key_bit_len = key_bit_len - lcp.size_bits() - 1;
+
+        let mut old_rem = prefix;
+        ok!(old_rem.skip_first(lcp.size_bits(), 0));
+        let old_to_right = ok!(old_rem.load_bit());
+
+        let old = {
+            let mut builder = CellBuilder::new();
+            ok!(write_label(&old_rem, child_key_bit_len, &mut builder));
+            ok!(builder.store_slice(data));
+            ok!(builder.build_ext(context))
+        };
+
+        let mut builder = CellBuilder::new();
+        ok!(write_label(&lcp, key_bit_len, &mut builder));
+        if lcp.size_bits() == key.size_bits() {
+            // The key ends inside the edge, so insert a node with a single child
+            ok!(builder.store_bit_one());
+            ok!(builder.store_bit(old_to_right));
+            ok!(builder.store_reference(old));
+            ok!(value.store_into(&mut builder, context));
+        } else {
+            // The key diverges from the edge, so insert a fork without a value
+            ok!(key.skip_first(lcp.size_bits() + 1, 0));
+            let new = ok!(make_var_leaf(&key, child_key_bit_len, value, context));
+            let (left, right) = if old_to_right { (new, old) } else { (old, new) };
+
+            ok!(builder.store_small_uint(0b01, 2));
+            ok!(builder.store_reference(left));
+            ok!(builder.store_reference(right));
+            ok!(builder.store_bit_zero());
+        }
+        return builder.build_ext(context).map(Some);
+    }
+
+    ok!(key.skip_first(prefix.size_bits(), 0));
+
+    let mut builder = CellBuilder::new();
+    ok!(builder.store_slice_data(raw_label));
+
+    match ok!(read_node(&mut data, remaining_bit_len)) {
+        VarNode::Leaf { value: old } => {
+            if key.is_data_empty() {
+                // Replace the value of the leaf
+                if !mode.can_replace() {
+                    return Ok(None);
+                }
+                ok!(builder.store_small_uint(0b00, 2));
+                ok!(value.store_into(&mut builder, context));
+            } else {
+                // Extend the leaf with a single child
+                if !mode.can_add() {
+                    return Ok(None);
+                }
+                let bit = ok!(key.load_bit());
+                let child = ok!(make_var_leaf(&key, remaining_bit_len - 1, value, context));
+
+                ok!(builder.store_bit_one());
+                ok!(builder.store_bit(bit));
+                ok!(builder.store_reference(child));
+                ok!(builder.store_slice(old));
+            }
+        }
+        VarNode::Fork { value: old } => {
+            if key.is_data_empty() {
+                // Set the value of the fork
+                let allowed = match old {
+                    Some(_) => mode.can_replace(),
+                    None => mode.can_add(),
+                };
+                if !allowed {
+                    return Ok(None);
+                }
+
+                ok!(builder.store_small_uint(0b01, 2));
+                // NOTE: only the children are copied, the old value is replaced
+                for cell in node.references().cloned().take(2) {
+                    ok!(builder.store_reference(cell));
+                }
+                ok!(builder.store_bit_one());
+                ok!(value.store_into(&mut builder, context));
+            } else {
+                let index = ok!(key.load_bit()) as u8;
+                let child = ok!(node.reference(index).ok_or(Error::CellUnderflow));
+                return match ok!(var_insert_impl(
+                    child,
+                    key,
+                    remaining_bit_len - 1,
+                    value,
+                    mode,
+                    context
+                )) {
+                    Some(child) => rebuild_with_child(node, index, child, context).map(Some),
+                    None => Ok(None),
+                };
+            }
+        }
+        VarNode::Cont { branch, value: old } => {
+            let Some(child) = node.reference_cloned(0) else {
+                return Err(Error::CellUnderflow);
+            };
+
+            if key.is_data_empty() {
+                // Replace the value of the node
+                if !mode.can_replace() {
+                    return Ok(None);
+                }
+                ok!(builder.store_bit_one());
+                ok!(builder.store_bit(branch));
+                ok!(builder.store_reference(child));
+                ok!(value.store_into(&mut builder, context));
+            } else if ok!(key.load_bit()) == branch {
+                return match ok!(var_insert_impl(
+                    child.as_ref(),
+                    key,
+                    remaining_bit_len - 1,
+                    value,
+                    mode,
+                    context
+                )) {
+                    Some(child) => rebuild_with_child(node, 0, child, context).map(Some),
+                    None => Ok(None),
+                };
+            } else {
+                // Convert the node into a fork
+                if !mode.can_add() {
+                    return Ok(None);
+                }
+                let new = ok!(make_var_leaf(&key, remaining_bit_len - 1, value, context));
+                let (left, right) = if branch { (new, child) } else { (child, new) };
+
+                ok!(builder.store_small_uint(0b01, 2));
+                ok!(builder.store_reference(left));
+                ok!(builder.store_reference(right));
+                ok!(builder.store_bit_one());
+                ok!(builder.store_slice(old));
+            }
+        }
+    }
+
+    builder.build_ext(context).map(Some)
+}
+
+fn var_dict_remove(
+    dict: &mut Option<Cell>,
+    key: CellSlice<'_>,
+    key_bit_len: u16,
+    context: &dyn CellContext,
+) -> Result<Option<CellSliceParts>, Error> {
+    if key.size_bits() > key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let Some(root) = dict else {
+        return Ok(None);
+    };
+
+    match ok!(var_remove_impl(root.clone(), key, key_bit_len, context)) {
+        Some((root, removed)) => {
+            *dict = root;
+            Ok(Some(removed))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns a rebuilt node (or `None` if it was removed) and the removed value.
+#[allow(clippy::type_complexity)]
+fn var_remove_impl(
+    node: Cell,
+    mut key: CellSlice<'_>,
+    key_bit_len: u16,
+    context: &dyn CellContext,
+) -> Result<Option<(Option<Cell>, CellSliceParts)>, Error> {
+    let node = ok!(context.load_cell(node, LoadMode::Full));
+    let mut data = ok!(node.as_slice());
+
+    let prefix = ok!(read_label(&mut data, key_bit_len));
+    let Some(remaining_bit_len) = key_bit_len.checked_sub(prefix.size_bits()) else {
+        return Err(Error::CellUnderflow);
+    };
+    let raw_label = node
+        .as_slice_allow_pruned()
+        .get_prefix(node.bit_len() - data.size_bits(), 0);
+
+    key = match key.strip_data_prefix(&prefix) {
+        Some(stripped_key) => stripped_key,
+        None => return Ok(None),
+    };
+
+    let mut builder = CellBuilder::new();
+    ok!(builder.store_slice_data(raw_label));
+
+    let (new_node, removed) = match ok!(read_node(&mut data, remaining_bit_len)) {
+        VarNode::Leaf { value } => {
+            if !key.is_data_empty() {
+                return Ok(None);
+            }
+            (None, value.range())
+        }
+        VarNode::Fork { value } if key.is_data_empty() => {
+            let Some(value) = value else {
+                return Ok(None);
+            };
+
+            // Remove the value of the fork
+            ok!(builder.store_small_uint(0b01, 2));
+            // NOTE: only the children are copied, the old value is removed
+            for cell in node.references().cloned().take(2) {
+                ok!(builder.store_reference(cell));
+            }
+            ok!(builder.store_bit_zero());
+            (Some(ok!(builder.build_ext(context))), value.range())
+        }
+        VarNode::Fork { value } => {
+            let index = ok!(key.load_bit()) as u8;
+            let child = ok!(node
+                .as_ref()
+                .reference_cloned(index)
+                .ok_or(Error::CellUnderflow));
+            let Some((child, removed)) =
+                ok!(var_remove_impl(child, key, remaining_bit_len - 1, context))
+            else {
+                return Ok(None);
+            };
+
+            let new_node = match (child, value) {
+                (Some(child), _) => ok!(rebuild_with_child(node.as_ref(), index, child, context)),
+                // Convert the fork into a node with a single child
+                (None, Some(value)) => {
+                    ok!(builder.store_bit_one());
+                    ok!(builder.store_bit(index == 0));
+                    ok!(builder.store_reference(ok!(node
+                        .as_ref()
+                        .reference_cloned(1 - index)
+                        .ok_or(Error::CellUnderflow))));
+                    ok!(builder.store_slice(value));
+                    ok!(builder.build_ext(context))
+                }
+                // Collapse the fork with the remaining child
+                (None, None) => {
+                    let opposite = ok!(node
+                        .as_ref()
+                        .reference(1 - index)
+                        .ok_or(Error::CellUnderflow));
+                    ok!(join_edge(
+                        &prefix,
+                        index == 0,
+                        opposite,
+                        key_bit_len,
+                        context
+                    ))
+                }
+            };
+            return Ok(Some((Some(new_node), removed)));
+        }
+        VarNode::Cont { branch, value } if key.is_data_empty() => {
+            // Collapse the node with its child
+            let child = ok!(node.as_ref().reference(0).ok_or(Error::CellUnderflow));
+            let new_node = ok!(join_edge(&prefix, branch, child, key_bit_len, context));
+            (Some(new_node), value.range())
+        }
+        VarNode::Cont { branch, value } => {
+            if ok!(key.load_bit()) != branch {
+                return Ok(None);
+            }
+
+            let child = ok!(node
+                .as_ref()
+                .reference_cloned(0)
+                .ok_or(Error::CellUnderflow));
+            let Some((child, removed)) =
+                ok!(var_remove_impl(child, key, remaining_bit_len - 1, context))
+            else {
+                return Ok(None);
+            };
+
+            let new_node = match child {
+                Some(child) => ok!(rebuild_with_child(node.as_ref(), 0, child, context)),
+                // Convert the node into a leaf
+                None => {
+                    ok!(builder.store_small_uint(0b00, 2));
+                    ok!(builder.store_slice(value));
+                    ok!(builder.build_ext(context))
+                }
+            };
+            return Ok(Some((Some(new_node), removed)));
+        }
+    };
+
+    Ok(Some((new_node, (node, removed))))
+}
+
+fn make_var_leaf(
+    key: &CellSlice<'_>,
+    key_bit_len: u16,
+    value: &dyn Store,
+    context: &dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    ok!(write_label(key, key_bit_len, &mut builder));
+    ok!(builder.store_small_uint(0b00, 2));
+    ok!(value.store_into(&mut builder, context));
+    builder.build_ext(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boc::BocRepr;
+
+    fn build_key(bits: &str) -> CellBuilder {
+        let mut builder = CellBuilder::new();
+        for bit in bits.chars() {
+            builder.store_bit(bit == '1').unwrap();
+        }
+        builder
+    }
+
+    fn key_to_string(key: &CellBuilder) -> String {
+        let slice = key.as_data_slice();
+        (0..slice.size_bits())
+            .map(|i| if slice.get_bit(i).unwrap() { '1' } else { '0' })
+            .collect()
+    }
+
+    fn build_dict<const N: u16>(keys: &[&str]) -> anyhow::Result<VarDict<N>> {
+        let mut dict = VarDict::<N>::new();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(dict.set(build_key(key).as_data_slice(), i as u16)?);
+        }
+        Ok(dict)
+    }
+
+    #[test]
+    fn var_dict_set_get() -> anyhow::Result<()> {
+        let keys = ["1011", "", "10", "0", "1", "101100", "1010", "111"];
+
+        let mut dict = build_dict::<16>(&keys)?;
+        for (i, key) in keys.iter().enumerate() {
+            let mut slice = dict.get(build_key(key).as_data_slice())?.unwrap();
+            assert_eq!(slice.load_u16()?, i as u16);
+        }
+        for key in ["00", "11", "100", "1011000", "10110", "1111"] {
+            assert!(!dict.contains_key(build_key(key).as_data_slice())?);
+        }
+
+        assert!(!dict.add(build_key("10").as_data_slice(), 100u16)?);
+        assert!(!dict.replace(build_key("11").as_data_slice(), 100u16)?);
+        assert!(dict.replace(build_key("10").as_data_slice(), 100u16)?);
+        assert!(dict.add(build_key("11").as_data_slice(), 200u16)?);
+        assert_eq!(
+            dict.get(build_key("10").as_data_slice())?
+                .unwrap()
+                .load_u16()?,
+            100
+        );
+        assert_eq!(
+            dict.get(build_key("11").as_data_slice())?
+                .unwrap()
+                .load_u16()?,
+            200
+        );
+
+        // Insertion order must not affect the tree
+        let mut reversed = keys;
+        reversed.reverse();
+        let mut other = VarDict::<16>::new();
+        for key in reversed {
+            let i = keys.iter().position(|k| *k == key).unwrap();
+            other.set(build_key(key).as_data_slice(), i as u16)?;
+        }
+        assert_eq!(other, build_dict::<16>(&keys)?);
+
+        // Too long key
+        let mut long_key = CellBuilder::new();
+        long_key.store_zeros(17)?;
+        assert!(dict.get(long_key.as_data_slice()).is_err());
+        assert!(dict.set(long_key.as_data_slice(), 0u16).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn var_dict_remove() -> anyhow::Result<()> {
+        let keys = [
+            "", "0", "00", "0001", "01", "1", "1000000", "1000001", "101",
+        ];
+
+        let mut dict = build_dict::<8>(&keys)?;
+        assert!(dict.remove(build_key("10").as_data_slice())?.is_none());
+        assert!(dict.remove(build_key("0011").as_data_slice())?.is_none());
+        assert!(dict.remove(build_key("000").as_data_slice())?.is_none());
+
+        // Remove keys in an order which touches all node kinds
+        let mut remaining = keys.to_vec();
+        for key in [
+            "0", "", "1000000", "00", "1", "101", "0001", "01", "1000001",
+        ] {
+            let i = keys.iter().position(|k| *k == key).unwrap();
+            let (cell, range) = dict.remove(build_key(key).as_data_slice())?.unwrap();
+            assert_eq!(range.apply(&cell)?.load_u16()?, i as u16);
+            assert!(!dict.contains_key(build_key(key).as_data_slice())?);
+
+            // Removal must produce the same tree as building without the key
+            remaining.retain(|k| *k != key);
+            let mut expected = VarDict::<8>::new();
+            for key in &remaining {
+                let j = keys.iter().position(|k| k == key).unwrap();
+                expected.set(build_key(key).as_data_slice(), j as u16)?;
+            }
+            assert_eq!(dict, expected);
+        }
+        assert!(dict.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn var_dict_fork_value_with_refs() -> anyhow::Result<()> {
+        let old_value = CellBuilder::build_from(0xaaaau16)?;
+        let new_value = CellBuilder::build_from(0xbbbbu16)?;
+
+        let build = |value: Option<&Cell>| -> anyhow::Result<VarDict<4>> {
+            let mut dict = VarDict::<4>::new();
+            dict.set(build_key("00").as_data_slice(), Cell::empty_cell())?;
+            dict.set(build_key("01").as_data_slice(), Cell::empty_cell())?;
+            if let Some(value) = value {
+                dict.set(build_key("0").as_data_slice(), value.clone())?;
+            }
+            Ok(dict)
+        };
+
+        // Replace the fork value
+        let mut dict = build(Some(&old_value))?;
+        assert!(dict.replace(build_key("0").as_data_slice(), new_value.clone())?);
+        let mut value = dict.get(build_key("0").as_data_slice())?.unwrap();
+        assert_eq!(value.size_refs(), 1);
+        assert_eq!(value.load_reference_cloned()?, new_value);
+        assert_eq!(dict, build(Some(&new_value))?);
+
+        // Remove the fork value
+        let (cell, range) = dict.remove(build_key("0").as_data_slice())?.unwrap();
+        assert_eq!(range.apply(&cell)?.load_reference_cloned()?, new_value);
+        assert!(!dict.contains_key(build_key("0").as_data_slice())?);
+        assert_eq!(dict, build(None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn var_dict_iter_and_serde() -> anyhow::Result<()> {
+        let keys = ["1111", "0", "11", "", "10", "110", "01"];
+        let dict = build_dict::<4>(&keys)?;
+
+        let encoded = BocRepr::encode_base64(&dict)?;
+        let decoded = BocRepr::decode_base64::<VarDict<4>, _>(encoded)?;
+        assert_eq!(decoded, dict);
+
+        let entries = decoded
+            .iter()
+            .map(|entry| {
+                let (key, mut value) = entry?;
+                Ok((key_to_string(&key), value.load_u16()?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        assert_eq!(
+            entries,
+            [
+                ("".to_owned(), 3),
+                ("0".to_owned(), 1),
+                ("01".to_owned(), 6),
+                ("10".to_owned(), 4),
+                ("11".to_owned(), 2),
+                ("110".to_owned(), 5),
+                ("1111".to_owned(), 0),
+            ]
+        );
+
+        Ok(())
+    }
+}
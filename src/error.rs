@@ -116,6 +116,96 @@ pub enum ParseBlockIdError {
     UnexpectedPart,
 }
 
+/// Error type for block proof verification.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum BlockProofError {
+    /// Merkle proof doesn't correspond to the block id.
+    #[error("invalid block Merkle proof")]
+    InvalidMerkleProof(#[source] Error),
+    /// Block proof doesn't contain signatures.
+    #[error("block proof has no signatures")]
+    NoSignatures,
+    /// Failed to read signatures.
+    #[error("invalid block signatures")]
+    InvalidSignatures(#[source] Error),
+    /// Signature was made by a node which is not in the validator set.
+    #[error("unknown validator {0}")]
+    UnknownValidator(crate::cell::HashBytes),
+    /// Several signatures were made by the same node.
+    #[error("duplicate signature from validator {0}")]
+    DuplicateSignature(crate::cell::HashBytes),
+    /// Signature check failed.
+    #[error("invalid signature from validator {0}")]
+    InvalidSignature(crate::cell::HashBytes),
+    /// Signers weight is not greater than 2/3 of the total weight.
+    #[error("insufficient signatures weight: {weight} of {total_weight}")]
+    InsufficientWeight {
+        /// Total weight of all valid signatures.
+        weight: u64,
+        /// Total weight of the validator set.
+        total_weight: u64,
+    },
+}
+
+/// Error type for light client related errors.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum LightClientError {
+    /// Block id doesn't match the provided data.
+    #[error("block id mismatch")]
+    BlockIdMismatch,
+    /// Proof is not for a newer masterchain block.
+    #[error("unexpected block {seqno}")]
+    UnexpectedBlock {
+        /// Seqno of the proven block.
+        seqno: u32,
+    },
+    /// Proven block is not a key block.
+    #[error("not a key block")]
+    NotKeyBlock,
+    /// Proven key block doesn't directly follow the trusted one.
+    #[error("unexpected previous key block {prev_key_block_seqno}")]
+    UnexpectedPrevKeyBlock {
+        /// Seqno of the previous key block of the proven block.
+        prev_key_block_seqno: u32,
+    },
+    /// Signatures were made by an unexpected validator subset.
+    #[error("validator subset mismatch")]
+    ValidatorSubsetMismatch,
+    /// Block proof verification failed.
+    #[error("invalid block proof")]
+    InvalidProof(#[from] BlockProofError),
+    /// Failed to read the block or config data.
+    #[error("invalid block data")]
+    InvalidData(#[source] Error),
+}
+
+/// Error type for block value flow validation.
+#[cfg(feature = "models")]
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum ValueFlowError {
+    /// Failed to read the block data.
+    #[error("invalid block data")]
+    InvalidData(#[source] Error),
+    /// Value flow component doesn't match the block content.
+    #[error("value flow `{component}` mismatch")]
+    ComponentMismatch {
+        /// Name of the value flow field.
+        component: &'static str,
+        /// Value recomputed from the block content.
+        expected: crate::models::CurrencyCollection,
+        /// Value stored in the value flow.
+        actual: crate::models::CurrencyCollection,
+    },
+    /// Incoming and outgoing values are not equal.
+    #[error("unbalanced value flow")]
+    Unbalanced {
+        /// Sum of all incoming values.
+        incoming: crate::models::CurrencyCollection,
+        /// Sum of all outgoing values.
+        outgoing: crate::models::CurrencyCollection,
+    },
+}
+
 /// Error type for global capability parsing related errors.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ParseGlobalCapabilityError {
@@ -79,6 +79,12 @@
 //!   access a subtotal of values for each subtree.
 //!   NOTE: this type is partially implemented due to its complexity.
 //!
+//! - [`PfxDict`] is a dictionary with variable-length keys which form a prefix code.
+//!   It is used to find a value by the longest key that is a prefix of some bit string.
+//!
+//! - [`VarDict`] is a dictionary with variable-length keys which can be prefixes
+//!   of each other.
+//!
 //! ## Supported Rust Versions
 //!
 //! This crate is built against the latest stable release. The minimum supported
@@ -104,6 +110,8 @@
 //! [`Dict`]: dict::Dict
 //! [`DictKey`]: dict::DictKey
 //! [`AugDict`]: dict::AugDict
+//! [`PfxDict`]: dict::PfxDict
+//! [`VarDict`]: dict::VarDict
 //! [`Load`]: cell::Load
 //! [`Store`]: cell::Store
 /// Prevents using `From::from` for plain error conversion.
@@ -0,0 +1,151 @@
+use super::{FilterAction, FilterContext, MerkleFilter};
+use crate::cell::HashBytes;
+
+/// Creates a Merkle filter from a closure.
+///
+/// The closure receives a representation hash of the cell and
+/// its depth relative to the root of the tree.
+///
+/// # Example
+///
+/// ```
+/// # use everscale_types::merkle::{filter_fn, FilterAction};
+/// // Include only the first three levels of the tree
+/// let filter = filter_fn(|_, depth| {
+///     if depth < 3 {
+///         FilterAction::Include
+///     } else {
+///         FilterAction::Skip
+///     }
+/// });
+/// ```
+pub fn filter_fn<F>(f: F) -> FilterFn<F>
+where
+    F: Fn(&HashBytes, u16) -> FilterAction,
+{
+    FilterFn(f)
+}
+
+/// A Merkle filter created from a closure.
+///
+/// See [`filter_fn`] for more details.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterFn<F>(F);
+
+impl<F> MerkleFilter for FilterFn<F>
+where
+    F: Fn(&HashBytes, u16) -> FilterAction,
+{
+    /// Checks the cell as if it was a root cell.
+    #[inline]
+    fn check(&self, cell: &HashBytes) -> FilterAction {
+        (self.0)(cell, 0)
+    }
+
+    #[inline]
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        (self.0)(cell, context.depth)
+    }
+}
+
+/// A Merkle filter which includes cells included by any of the two filters.
+///
+/// See [`MerkleFilter::or`] for more details.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterOr<A, B>(pub(crate) A, pub(crate) B);
+
+impl<A: MerkleFilter, B: MerkleFilter> MerkleFilter for FilterOr<A, B> {
+    fn check(&self, cell: &HashBytes) -> FilterAction {
+        match self.0.check(cell) {
+            FilterAction::IncludeSubtree => FilterAction::IncludeSubtree,
+            action => std::cmp::max(action, self.1.check(cell)),
+        }
+    }
+
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        match self.0.check_ext(cell, context) {
+            FilterAction::IncludeSubtree => FilterAction::IncludeSubtree,
+            action => std::cmp::max(action, self.1.check_ext(cell, context)),
+        }
+    }
+}
+
+/// A Merkle filter which includes cells included by both filters.
+///
+/// See [`MerkleFilter::and`] for more details.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterAnd<A, B>(pub(crate) A, pub(crate) B);
+
+impl<A: MerkleFilter, B: MerkleFilter> MerkleFilter for FilterAnd<A, B> {
+    fn check(&self, cell: &HashBytes) -> FilterAction {
+        match self.0.check(cell) {
+            FilterAction::Skip => FilterAction::Skip,
+            action => std::cmp::min(action, self.1.check(cell)),
+        }
+    }
+
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        match self.0.check_ext(cell, context) {
+            FilterAction::Skip => FilterAction::Skip,
+            action => std::cmp::min(action, self.1.check_ext(cell, context)),
+        }
+    }
+}
+
+/// A Merkle filter which includes cells skipped by the inner filter.
+///
+/// See [`MerkleFilter::not`] for more details.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterNot<F>(pub(crate) F);
+
+impl<F> FilterNot<F> {
+    #[inline]
+    fn invert(action: FilterAction) -> FilterAction {
+        if action == FilterAction::Skip {
+            FilterAction::Include
+        } else {
+            FilterAction::Skip
+        }
+    }
+}
+
+impl<F: MerkleFilter> MerkleFilter for FilterNot<F> {
+    #[inline]
+    fn check(&self, cell: &HashBytes) -> FilterAction {
+        Self::invert(self.0.check(cell))
+    }
+
+    #[inline]
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        Self::invert(self.0.check_ext(cell, context))
+    }
+}
+
+/// A Merkle filter which skips all cells deeper than the specified depth.
+///
+/// See [`MerkleFilter::max_depth`] for more details.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxDepth<F> {
+    pub(crate) filter: F,
+    pub(crate) depth: u16,
+}
+
+impl<F: MerkleFilter> MerkleFilter for MaxDepth<F> {
+    /// Checks the cell as if it was a root cell.
+    #[inline]
+    fn check(&self, cell: &HashBytes) -> FilterAction {
+        self.filter.check(cell)
+    }
+
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        if context.depth > self.depth {
+            return FilterAction::Skip;
+        }
+
+        match self.filter.check_ext(cell, context) {
+            // Subtree must also be checked for the depth limit
+            FilterAction::IncludeSubtree => FilterAction::Include,
+            action => action,
+        }
+    }
+}
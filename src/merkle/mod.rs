@@ -3,12 +3,14 @@
 use std::collections::HashSet;
 use std::hash::BuildHasher;
 
-use crate::cell::{HashBytes, UsageTree, UsageTreeWithSubtrees};
+use crate::cell::{CellDescriptor, HashBytes, UsageTree, UsageTreeWithSubtrees};
 
+pub use self::filter::{filter_fn, FilterAnd, FilterFn, FilterNot, FilterOr, MaxDepth};
 pub use self::proof::{MerkleProof, MerkleProofBuilder, MerkleProofExtBuilder, MerkleProofRef};
 pub use self::pruned_branch::make_pruned_branch;
 pub use self::update::{MerkleUpdate, MerkleUpdateBuilder};
 
+mod filter;
 mod proof;
 mod pruned_branch;
 mod update;
@@ -30,10 +32,75 @@ mod __checks {
 pub trait MerkleFilter {
     /// Returns how the cell should be included in the Merkle proof or update.
     fn check(&self, cell: &HashBytes) -> FilterAction;
+
+    /// Returns how the cell should be included in the Merkle proof or update,
+    /// using some additional info about the cell.
+    ///
+    /// Uses [`check`] by default.
+    ///
+    /// [`check`]: MerkleFilter::check
+    #[inline]
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        _ = context;
+        self.check(cell)
+    }
+
+    /// Creates a filter which includes cells included by any of the filters.
+    ///
+    /// The most inclusive action of the two filters is used.
+    fn or<F: MerkleFilter>(self, other: F) -> FilterOr<Self, F>
+    where
+        Self: Sized,
+    {
+        FilterOr(self, other)
+    }
+
+    /// Creates a filter which includes cells included by both filters.
+    ///
+    /// The least inclusive action of the two filters is used.
+    fn and<F: MerkleFilter>(self, other: F) -> FilterAnd<Self, F>
+    where
+        Self: Sized,
+    {
+        FilterAnd(self, other)
+    }
+
+    /// Creates a filter which includes cells skipped by this filter
+    /// and skips all other cells.
+    fn not(self) -> FilterNot<Self>
+    where
+        Self: Sized,
+    {
+        FilterNot(self)
+    }
+
+    /// Creates a filter which skips all cells deeper than the specified depth.
+    ///
+    /// NOTE: Included subtrees are checked cell by cell, so the
+    /// underlying filter must handle their children as well.
+    fn max_depth(self, depth: u16) -> MaxDepth<Self>
+    where
+        Self: Sized,
+    {
+        MaxDepth {
+            filter: self,
+            depth,
+        }
+    }
+}
+
+/// Additional info about the cell which is being checked by [`MerkleFilter`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct FilterContext {
+    /// Cell depth relative to the root of the tree (root has depth 0).
+    pub depth: u16,
+    /// Cell descriptor.
+    pub descriptor: CellDescriptor,
 }
 
 /// Merkle filter action.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub enum FilterAction {
     /// Skip this cell and its subtree.
     Skip,
@@ -48,6 +115,11 @@ impl<T: MerkleFilter + ?Sized> MerkleFilter for &T {
     fn check(&self, cell: &HashBytes) -> FilterAction {
         <T as MerkleFilter>::check(self, cell)
     }
+
+    #[inline]
+    fn check_ext(&self, cell: &HashBytes, context: &FilterContext) -> FilterAction {
+        <T as MerkleFilter>::check_ext(self, cell, context)
+    }
 }
 
 impl MerkleFilter for UsageTree {
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::hash::BuildHasher;
 
-use super::{make_pruned_branch, FilterAction, MerkleFilter};
+use super::{make_pruned_branch, FilterAction, FilterContext, MerkleFilter};
 use crate::cell::*;
 use crate::error::Error;
 
@@ -210,6 +210,201 @@ impl MerkleProof {
 
         MerkleProofBuilder::new(root, RootOrChild { cells, child_hash })
     }
+
+    /// Checks that the proof corresponds to the tree with the specified root hash.
+    ///
+    /// Verifies that the virtual hash and depth of the proof root match
+    /// the stored ones and that all pruned branches were made for this proof.
+    ///
+    /// Returns a virtualized root cell of the original tree.
+    pub fn verify(&self, expected_hash: &HashBytes) -> Result<Cell, Error> {
+        if self.hash != *expected_hash
+            || self.cell.hash(0) != expected_hash
+            || self.cell.depth(0) != self.depth
+        {
+            return Err(Error::InvalidData);
+        }
+
+        ok!(check_pruned_branches(self.cell.as_ref()));
+        Ok(Cell::virtualize(self.cell.clone()))
+    }
+
+    /// Combines proofs of the same tree into a single proof.
+    ///
+    /// Pruned branches are replaced with subtrees from other proofs
+    /// wherever they contain them.
+    pub fn merge(proofs: &[MerkleProof]) -> Result<Self, Error> {
+        Self::merge_ext(proofs, Cell::empty_context())
+    }
+
+    /// Combines proofs of the same tree into a single proof
+    /// using the specified cell context.
+    ///
+    /// Pruned branches are replaced with subtrees from other proofs
+    /// wherever they contain them.
+    pub fn merge_ext(proofs: &[MerkleProof], context: &dyn CellContext) -> Result<Self, Error> {
+        let Some(first) = proofs.first() else {
+            return Err(Error::EmptyProof);
+        };
+        if proofs
+            .iter()
+            .any(|proof| proof.hash != first.hash || proof.depth != first.depth)
+        {
+            return Err(Error::InvalidData);
+        }
+
+        let cells = proofs.iter().map(|proof| proof.cell.clone()).collect();
+        Ok(Self {
+            hash: first.hash,
+            depth: first.depth,
+            cell: ok!(merge_cells(cells, context)),
+        })
+    }
+}
+
+/// Merges several versions of the same cell with different pruned subtrees.
+fn merge_cells(cells: Vec<Cell>, context: &dyn CellContext) -> Result<Cell, Error> {
+    enum Resolved {
+        Done(Cell),
+        Expand(Vec<Cell>),
+    }
+
+    struct Node {
+        cells: Vec<Cell>,
+        merkle_depth: u8,
+        next_ref: u8,
+        children: CellRefsBuilder,
+    }
+
+    // NOTE: `merkle_depth` is a merkle depth of the parent cell
+    let resolve = |cells: Vec<Cell>,
+                   merkle_depth: u8,
+                   merged: &ahash::HashMap<(HashBytes, u8), Cell>|
+     -> Result<Resolved, Error> {
+        let hash = *cells[0].hash(merkle_depth);
+        if cells.iter().any(|cell| *cell.hash(merkle_depth) != hash) {
+            return Err(Error::InvalidData);
+        }
+        if let Some(cell) = merged.get(&(hash, merkle_depth)) {
+            return Ok(Resolved::Done(cell.clone()));
+        }
+
+        // Skip pruned branches which were made for this proof
+        let mut full = cells
+            .iter()
+            .filter(|cell| {
+                let descriptor = cell.descriptor();
+                !descriptor.is_pruned_branch()
+                    || !descriptor.level_mask().contains(merkle_depth + 1)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(match full.len() {
+            0 => Resolved::Done(cells[0].clone()),
+            _ if full
+                .iter()
+                .all(|cell| cell.repr_hash() == full[0].repr_hash()) =>
+            {
+                Resolved::Done(full.swap_remove(0))
+            }
+            _ => Resolved::Expand(full),
+        })
+    };
+
+    let mut merged = ahash::HashMap::<(HashBytes, u8), Cell>::default();
+    let cells = match ok!(resolve(cells, 0, &merged)) {
+        Resolved::Done(cell) => return Ok(cell),
+        Resolved::Expand(cells) => cells,
+    };
+
+    let mut stack = vec![Node {
+        merkle_depth: cells[0].descriptor().is_merkle() as u8,
+        cells,
+        next_ref: 0,
+        children: CellRefsBuilder::default(),
+    }];
+
+    while let Some(last) = stack.last_mut() {
+        let template = &last.cells[0];
+        if last.next_ref < template.reference_count() {
+            // Process children if they are left
+            let index = last.next_ref;
+            last.next_ref += 1;
+
+            let mut children = Vec::with_capacity(last.cells.len());
+            for cell in &last.cells {
+                match cell.reference_cloned(index) {
+                    Some(child) => children.push(child),
+                    None => return Err(Error::InvalidData),
+                }
+            }
+
+            match ok!(resolve(children, last.merkle_depth, &merged)) {
+                Resolved::Done(child) => {
+                    ok!(last.children.store_reference(child));
+                }
+                Resolved::Expand(cells) => {
+                    let merkle_depth = last.merkle_depth + cells[0].descriptor().is_merkle() as u8;
+                    stack.push(Node {
+                        cells,
+                        merkle_depth,
+                        next_ref: 0,
+                        children: CellRefsBuilder::default(),
+                    });
+                }
+            }
+        } else if let Some(last) = stack.pop() {
+            // Build a new cell if there are no child nodes left to process
+            let template = last.cells[0].as_ref();
+
+            let mut builder = CellBuilder::new();
+            builder.set_exotic(template.descriptor().is_exotic());
+            ok!(builder.store_cell_data(template));
+            builder.set_references(last.children);
+            let cell = ok!(builder.build_ext(context));
+
+            match stack.last_mut() {
+                // Append this cell to the ancestor
+                Some(parent) => {
+                    merged.insert(
+                        (*cell.hash(parent.merkle_depth), parent.merkle_depth),
+                        cell.clone(),
+                    );
+                    ok!(parent.children.store_reference(cell));
+                }
+                // Or return it as a result (for the root node)
+                None => return Ok(cell),
+            }
+        }
+    }
+
+    // Something is wrong if we are here
+    Err(Error::EmptyProof)
+}
+
+/// Checks that each pruned branch contains hashes for the level
+/// of the Merkle proof (or update) it belongs to.
+pub(crate) fn check_pruned_branches(root: &DynCell) -> Result<(), Error> {
+    let mut visited = ahash::HashSet::<(&HashBytes, u8)>::default();
+    let mut stack = vec![(root, root.descriptor().is_merkle() as u8)];
+    while let Some((cell, merkle_depth)) = stack.pop() {
+        for child in cell.references() {
+            let descriptor = child.descriptor();
+            if descriptor.is_pruned_branch() {
+                if !descriptor.level_mask().contains(merkle_depth + 1) {
+                    return Err(Error::InvalidCell);
+                }
+                continue;
+            }
+
+            let child_depth = merkle_depth + descriptor.is_merkle() as u8;
+            if visited.insert((child.repr_hash(), child_depth)) {
+                stack.push((child, child_depth));
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Helper struct to build a Merkle proof.
@@ -339,12 +534,18 @@ where
         struct Node<'a> {
             references: RefsIter<'a>,
             descriptor: CellDescriptor,
+            depth: u16,
             merkle_depth: u8,
             children: CellRefsBuilder,
         }
 
+        let root_descriptor = self.root.descriptor();
+        let root_context = FilterContext {
+            depth: 0,
+            descriptor: root_descriptor,
+        };
         if !self.allow_different_root
-            && self.filter.check(self.root.repr_hash()) == FilterAction::Skip
+            && self.filter.check_ext(self.root.repr_hash(), &root_context) == FilterAction::Skip
         {
             return Err(Error::EmptyProof);
         }
@@ -352,10 +553,10 @@ where
         let mut stack = Vec::with_capacity(self.root.repr_depth() as usize);
 
         // Push root node
-        let root_descriptor = self.root.descriptor();
         stack.push(Node {
             references: self.root.references(),
             descriptor: root_descriptor,
+            depth: 0,
             merkle_depth: root_descriptor.is_merkle() as u8,
             children: CellRefsBuilder::default(),
         });
@@ -373,7 +574,11 @@ where
                     let descriptor = child.descriptor();
 
                     // Check if child is in a tree
-                    match self.filter.check(child_repr_hash) {
+                    let context = FilterContext {
+                        depth: last.depth + 1,
+                        descriptor,
+                    };
+                    match self.filter.check_ext(child_repr_hash, &context) {
                         // Included subtrees are used as is
                         FilterAction::IncludeSubtree => {
                             last.references.peek_prev_cloned().expect("mut not fail")
@@ -404,6 +609,7 @@ where
                             stack.push(Node {
                                 references: child.references(),
                                 descriptor,
+                                depth: context.depth,
                                 merkle_depth,
                                 children: CellRefsBuilder::default(),
                             });
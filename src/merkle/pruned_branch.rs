@@ -2,13 +2,21 @@ use crate::cell::*;
 use crate::error::Error;
 
 /// Creates a pruned branch cell with the specified merkle depth.
+///
+/// Only levels up to the `merkle_depth` are stored, so that virtualized
+/// cells inside nested Merkle structures are pruned correctly.
 pub fn make_pruned_branch(
     cell: &DynCell,
     merkle_depth: u8,
     context: &dyn CellContext,
 ) -> Result<Cell, Error> {
+    if merkle_depth >= LevelMask::MAX_LEVEL {
+        return Err(Error::DepthOverflow);
+    }
+
     let descriptor = cell.descriptor();
-    let cell_level_mask = descriptor.level_mask();
+    let cell_level_mask =
+        LevelMask::new(descriptor.level_mask().to_byte() & ((1 << merkle_depth) - 1));
 
     let mut builder = CellBuilder::new();
     let level_mask = LevelMask::new(cell_level_mask.to_byte() | (1 << merkle_depth));
@@ -34,6 +42,7 @@ pub fn make_pruned_branch(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::boc::Boc;
 
     #[test]
     fn correct_pruned_branch() {
@@ -56,4 +65,40 @@ mod test {
             make_pruned_branch(virtual_cell, 0, Cell::empty_context()).unwrap();
         assert_eq!(pruned_branch.as_ref(), virtual_pruned_branch.as_ref());
     }
+
+    #[test]
+    fn multi_level_pruned_branch() {
+        let child = Cell::empty_cell();
+        let pruned_child = make_pruned_branch(child.as_ref(), 0, Cell::empty_context()).unwrap();
+
+        // Level 1 cell
+        let cell = {
+            let mut builder = CellBuilder::new();
+            builder.store_u32(123).unwrap();
+            builder.store_reference(pruned_child).unwrap();
+            builder.build().unwrap()
+        };
+        assert_eq!(cell.level_mask(), LevelMask::new(0b001));
+
+        // Level 2 pruned branch
+        let pruned_branch = make_pruned_branch(cell.as_ref(), 1, Cell::empty_context()).unwrap();
+        assert_eq!(pruned_branch.level_mask(), LevelMask::new(0b011));
+        for level in 0..=1 {
+            assert_eq!(pruned_branch.hash(level), cell.hash(level));
+            assert_eq!(pruned_branch.depth(level), cell.depth(level));
+        }
+
+        let decoded = Boc::decode(Boc::encode(pruned_branch.as_ref())).unwrap();
+        assert_eq!(decoded.as_ref(), pruned_branch.as_ref());
+
+        // Virtualization removes only the outer level
+        let virtual_cell = pruned_branch.virtualize();
+        assert_eq!(virtual_cell.repr_hash(), cell.repr_hash());
+        assert_eq!(virtual_cell.repr_depth(), cell.repr_depth());
+
+        assert_eq!(
+            make_pruned_branch(cell.as_ref(), 3, Cell::empty_context()).unwrap_err(),
+            Error::DepthOverflow
+        );
+    }
 }
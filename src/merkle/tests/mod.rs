@@ -31,6 +31,68 @@ fn test_proof() {
     assert_eq!(root.as_ref().repr_depth(), virtual_root.repr_depth());
 }
 
+#[test]
+fn verify_proof() -> anyhow::Result<()> {
+    let root = Boc::decode(include_bytes!("simple_proof.boc"))?;
+    let target_hash = root.as_ref().reference(1).unwrap().repr_hash();
+
+    let proof = MerkleProof::create_for_cell(root.as_ref(), target_hash).build()?;
+
+    let virtual_root = proof.verify(root.repr_hash())?;
+    assert_eq!(virtual_root.repr_hash(), root.repr_hash());
+    assert_eq!(virtual_root.repr_depth(), root.repr_depth());
+
+    // Wrong root hash
+    assert_eq!(
+        proof.verify(&HashBytes([0x11; 32])).unwrap_err(),
+        Error::InvalidData
+    );
+
+    // Stored hash doesn't match the proof cell
+    let mut invalid = proof.clone();
+    invalid.hash = HashBytes([0x11; 32]);
+    assert_eq!(
+        invalid.verify(&HashBytes([0x11; 32])).unwrap_err(),
+        Error::InvalidData
+    );
+
+    // Wrong depth
+    let mut invalid = proof.clone();
+    invalid.depth += 1;
+    assert_eq!(
+        invalid.verify(root.repr_hash()).unwrap_err(),
+        Error::InvalidData
+    );
+
+    // Pruned branch which was made for a different merkle depth
+    let child = {
+        let mut b = CellBuilder::new();
+        b.store_u32(123)?;
+        b.store_reference(Cell::empty_cell())?;
+        b.build()?
+    };
+    let cell = {
+        let mut b = CellBuilder::new();
+        b.store_reference(make_pruned_branch(
+            child.as_ref(),
+            1,
+            Cell::empty_context(),
+        )?)?;
+        b.build()?
+    };
+    let invalid = MerkleProof {
+        hash: *cell.hash(0),
+        depth: cell.depth(0),
+        cell,
+    };
+    assert_eq!(
+        invalid.verify(&invalid.hash).unwrap_err(),
+        Error::InvalidCell
+    );
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)] // takes too long to execute on miri
 fn create_proof_for_deep_cell() {
@@ -71,6 +133,64 @@ fn create_proof_for_deep_cell() {
     assert_eq!(cell, decoded);
 }
 
+#[test]
+fn merge_proofs() -> anyhow::Result<()> {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..100 {
+        dict.add(i, i * 10)?;
+    }
+    let root = CellBuilder::build_from(&dict)?;
+
+    let prove = |keys: &[u32]| -> anyhow::Result<MerkleProof> {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = usage_tree.track(&root).parse::<Dict<u32, u32>>()?;
+        for key in keys {
+            tracked.get(key)?;
+        }
+        Ok(MerkleProof::create(root.as_ref(), usage_tree).build()?)
+    };
+
+    let key_sets: [&[u32]; 4] = [&[3], &[50, 51], &[99], &[3, 1000]];
+    let proofs = key_sets
+        .iter()
+        .map(|keys| prove(keys))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // Merged proof must be the same as the one built from the union of usage trees
+    let merged = MerkleProof::merge(&proofs)?;
+    assert_eq!(merged, prove(&[3, 50, 51, 99, 1000])?);
+
+    let virtual_root = merged.verify(root.repr_hash())?;
+    let merged_dict = virtual_root.parse::<Dict<u32, u32>>()?;
+    for key in [3, 50, 51, 99] {
+        assert_eq!(merged_dict.get(key)?, Some(key * 10));
+    }
+    assert_eq!(merged_dict.get(1000)?, None);
+    assert!(merged_dict.get(10).is_err());
+
+    // Merging a single proof or identical proofs doesn't change it
+    assert_eq!(MerkleProof::merge(&proofs[..1])?, proofs[0]);
+    assert_eq!(
+        MerkleProof::merge(&[proofs[1].clone(), proofs[1].clone()])?,
+        proofs[1]
+    );
+
+    // Proofs of different trees can't be merged
+    let other = prove(&[0])?;
+    let mut other_dict = dict.clone();
+    other_dict.set(0, 1)?;
+    let other_root = CellBuilder::build_from(&other_dict)?;
+    let filter = std::collections::HashSet::from([*other_root.repr_hash()]);
+    let different = MerkleProof::create(other_root.as_ref(), filter).build()?;
+    assert_eq!(
+        MerkleProof::merge(&[other, different]).unwrap_err(),
+        Error::InvalidData
+    );
+    assert_eq!(MerkleProof::merge(&[]).unwrap_err(), Error::EmptyProof);
+
+    Ok(())
+}
+
 #[test]
 fn create_proof_for_dict() {
     // Create dict with keys 0..10
@@ -253,3 +373,315 @@ fn test_merkle_update() {
     // Check that both Merkle updates are the same
     assert_eq!(merkle_update, merkle_update_with_usage_tree);
 }
+
+#[test]
+fn compose_merkle_updates() -> anyhow::Result<()> {
+    fn update_dict(
+        root: &Cell,
+        f: impl FnOnce(&mut Dict<u32, u32>) -> Result<(), Error>,
+    ) -> anyhow::Result<(Cell, MerkleUpdate)> {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let mut dict = usage_tree.track(root).parse::<Dict<u32, u32>>()?;
+        f(&mut dict)?;
+
+        let new_root = CellBuilder::build_from(&dict)?;
+        let update = MerkleUpdate::create(root.as_ref(), new_root.as_ref(), usage_tree).build()?;
+        update.validate()?;
+        Ok((new_root, update))
+    }
+
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..1000 {
+        dict.set(i, i)?;
+    }
+    let a = CellBuilder::build_from(&dict)?;
+
+    let (b, a_to_b) = update_dict(&a, |dict| {
+        dict.set(1, 100)?;
+        dict.set(500, 100)?;
+        dict.set(2000, 100).map(|_| ())
+    })?;
+    let (c, b_to_c) = update_dict(&b, |dict| {
+        dict.set(500, 200)?;
+        dict.set(999, 200)?;
+        dict.remove(250)?;
+        dict.set(3000, 200).map(|_| ())
+    })?;
+    let (d, c_to_d) = update_dict(&c, |dict| dict.remove(1).map(|_| ()))?;
+
+    let a_to_c = MerkleUpdate::compose(&a_to_b, &b_to_c)?;
+    assert_eq!(a_to_c.old_hash, *a.repr_hash());
+    assert_eq!(a_to_c.new_hash, *c.repr_hash());
+    assert_eq!(a_to_c.apply(&a)?.as_ref(), c.as_ref());
+
+    let a_to_d = MerkleUpdate::compose(&a_to_c, &c_to_d)?;
+    assert_eq!(a_to_d.apply(&a)?.as_ref(), d.as_ref());
+    let b_to_d = MerkleUpdate::compose(&b_to_c, &c_to_d)?;
+    assert_eq!(
+        MerkleUpdate::compose(&a_to_b, &b_to_d)?.apply(&a)?.as_ref(),
+        d.as_ref()
+    );
+
+    // Updates must be consecutive
+    assert_eq!(
+        MerkleUpdate::compose(&b_to_c, &a_to_b).unwrap_err(),
+        Error::InvalidData
+    );
+
+    // Inconsistent updates are rejected
+    let mut invalid = a_to_b.clone();
+    invalid.new_hash = b_to_c.new_hash;
+    assert_eq!(invalid.validate().unwrap_err(), Error::InvalidData);
+
+    // Unchanged cells of the new tree must be in the old tree
+    let mut other = Dict::<u32, u32>::new();
+    for i in 0..100 {
+        other.set(i, i + 1)?;
+    }
+    let other = CellBuilder::build_from(&other)?;
+    let filter = std::collections::HashSet::from([*other.repr_hash()]);
+    let mut invalid = a_to_b.clone();
+    invalid.new =
+        MerkleProof::create(other.as_ref(), filter).build_raw_ext(Cell::empty_context())?;
+    invalid.new_hash = *other.repr_hash();
+    invalid.new_depth = other.repr_depth();
+    assert_eq!(invalid.validate().unwrap_err(), Error::InvalidData);
+
+    Ok(())
+}
+
+#[test]
+fn nested_merkle_proofs() -> anyhow::Result<()> {
+    fn build_dict(len: u32, salt: u32) -> anyhow::Result<Cell> {
+        let mut dict = Dict::<u32, u32>::new();
+        for i in 0..len {
+            dict.set(i, i + salt)?;
+        }
+        Ok(CellBuilder::build_from(dict)?)
+    }
+
+    fn prove<F: FnOnce(&DynCell) -> anyhow::Result<()>>(
+        root: &Cell,
+        visit: F,
+    ) -> anyhow::Result<MerkleProof> {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let tracked = usage_tree.track(root);
+        tracked.as_ref().data();
+        visit(tracked.as_ref())?;
+        Ok(MerkleProof::create(root.as_ref(), usage_tree).build()?)
+    }
+
+    fn check_same(virt: &DynCell, orig: &DynCell) {
+        assert_eq!(virt.repr_hash(), orig.repr_hash());
+        assert_eq!(virt.repr_depth(), orig.repr_depth());
+        if virt.descriptor().is_pruned_branch() && !orig.descriptor().is_pruned_branch() {
+            return;
+        }
+        for (virt, orig) in virt.references().zip(orig.references()) {
+            check_same(virt, orig);
+        }
+    }
+
+    // Level 1
+    let inner_root = build_dict(100, 0)?;
+    let inner = prove(&inner_root, |cell| {
+        cell.parse::<Dict<u32, u32>>()?.get(5)?;
+        Ok(())
+    })?;
+    let inner_cell = CellBuilder::build_from(&inner)?;
+
+    // Level 2
+    let mut builder = CellBuilder::new();
+    builder.store_u32(123)?;
+    builder.store_reference(inner_cell)?;
+    builder.store_reference(build_dict(50, 7)?)?;
+    let root2 = builder.build()?;
+
+    let outer = prove(&root2, |cell| {
+        let proof = cell.reference(0).unwrap().parse::<MerkleProofRef>()?;
+        proof.cell.virtualize().parse::<Dict<u32, u32>>()?.get(5)?;
+        Ok(())
+    })?;
+    let outer_cell = CellBuilder::build_from(&outer)?;
+
+    let virtual_root = outer.cell.virtualize();
+    check_same(virtual_root, root2.as_ref());
+
+    let proof = virtual_root
+        .reference(0)
+        .unwrap()
+        .parse::<MerkleProofRef>()?;
+    assert_eq!(proof.hash, *inner_root.repr_hash());
+    check_same(proof.cell.virtualize(), inner_root.as_ref());
+
+    // Cells from the inner proof are still available through the outer one
+    let dict = proof.cell.virtualize().parse::<Dict<u32, u32>>()?;
+    assert_eq!(dict.get(5)?, Some(5));
+    assert!(matches!(dict.get(99), Err(Error::PrunedBranchAccess)));
+
+    // Level 3
+    let mut builder = CellBuilder::new();
+    builder.store_reference(outer_cell)?;
+    builder.store_reference(build_dict(10, 1)?)?;
+    let root3 = builder.build()?;
+
+    let outer2 = prove(&root3, |cell| {
+        let proof = cell.reference(0).unwrap().parse::<MerkleProofRef>()?;
+        let root2 = proof.cell.virtualize();
+        root2.data();
+        let proof = root2.reference(0).unwrap().parse::<MerkleProofRef>()?;
+        proof.cell.virtualize().data();
+        Ok(())
+    })?;
+
+    // Owned cells are virtualized the same way as borrowed ones
+    let outer2 =
+        Boc::decode(Boc::encode(CellBuilder::build_from(&outer2)?))?.parse::<MerkleProof>()?;
+    let virtual_root = Cell::virtualize(outer2.cell.clone());
+    check_same(virtual_root.as_ref(), root3.as_ref());
+
+    let proof = virtual_root.as_ref().reference_cloned(0).unwrap();
+    let proof = proof.parse::<MerkleProof>()?;
+    check_same(proof.cell.as_ref().virtualize(), root2.as_ref());
+
+    let proof = Cell::virtualize(proof.cell)
+        .as_ref()
+        .reference(0)
+        .unwrap()
+        .parse::<MerkleProof>()?;
+    check_same(
+        Cell::virtualize(proof.cell.clone()).as_ref(),
+        inner_root.as_ref(),
+    );
+    assert_eq!(
+        proof.cell.as_ref().virtualize().repr_hash(),
+        inner_root.repr_hash()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn nested_block_proof() -> anyhow::Result<()> {
+    use crate::models::{Block, BlockProof};
+
+    let boc = Boc::decode(include_bytes!(
+        "../../models/block/tests/mc_block_proof.boc"
+    ))?;
+    let block_proof = boc.parse::<BlockProof>()?;
+
+    let proof = block_proof.root.parse::<MerkleProof>()?;
+    let virtual_block = proof.verify(&block_proof.proof_for.root_hash)?;
+
+    let block = virtual_block.parse::<Block>()?;
+    let info = block.info.load()?;
+    assert_eq!(info.seqno, block_proof.proof_for.seqno);
+
+    // State update is a Merkle cell inside the Merkle proof
+    let state_update = block.state_update.load()?;
+    for (cell, hash, depth) in [
+        (
+            &state_update.old,
+            &state_update.old_hash,
+            state_update.old_depth,
+        ),
+        (
+            &state_update.new,
+            &state_update.new_hash,
+            state_update.new_depth,
+        ),
+    ] {
+        let state_root = cell.as_ref().virtualize();
+        assert_eq!(state_root.repr_hash(), hash);
+        assert_eq!(state_root.repr_depth(), depth);
+    }
+
+    // Lazy cells keep the virtualized state update cell
+    let state_update_cell = block.state_update.into_inner();
+    assert_eq!(
+        state_update_cell.repr_hash(),
+        virtual_block.as_ref().reference(2).unwrap().repr_hash()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn filter_combinators() -> anyhow::Result<()> {
+    use std::collections::HashSet;
+
+    fn pruned_depths(cell: &DynCell, depth: u16, result: &mut Vec<u16>) {
+        if cell.descriptor().is_pruned_branch() {
+            result.push(depth);
+        }
+        for child in cell.references() {
+            pruned_depths(child, depth + 1, result);
+        }
+    }
+
+    let mut builder = CellBuilder::new();
+    builder.store_reference(create_tree(5, 1).build()?)?;
+    builder.store_reference(create_tree(5, 2).build()?)?;
+    let root = builder.build()?;
+
+    let left = root.reference(0).unwrap();
+    let right = root.reference(1).unwrap();
+
+    // Closure filter
+    let filter = filter_fn(|_, depth| {
+        if depth <= 2 {
+            FilterAction::Include
+        } else {
+            FilterAction::Skip
+        }
+    });
+    let proof = MerkleProof::create(root.as_ref(), filter).build()?;
+    let mut depths = Vec::new();
+    pruned_depths(proof.cell.as_ref(), 0, &mut depths);
+    assert_eq!(depths, [3, 3]);
+    assert_eq!(
+        proof.cell.as_ref().virtualize().repr_hash(),
+        root.repr_hash()
+    );
+
+    // Max depth
+    let all = filter_fn(|_, _| FilterAction::IncludeSubtree);
+    let proof = MerkleProof::create(root.as_ref(), all.max_depth(2)).build()?;
+    let mut depths = Vec::new();
+    pruned_depths(proof.cell.as_ref(), 0, &mut depths);
+    assert_eq!(depths, [3, 3]);
+
+    // Or
+    let root_hash = *root.repr_hash();
+    let left_set = HashSet::from([root_hash, *left.repr_hash()]);
+    let right_set = HashSet::from([root_hash, *right.repr_hash()]);
+    let both_set = HashSet::from([root_hash, *left.repr_hash(), *right.repr_hash()]);
+
+    let proof = MerkleProof::create(root.as_ref(), (&left_set).or(&right_set)).build()?;
+    assert_eq!(
+        proof,
+        MerkleProof::create(root.as_ref(), &both_set).build()?
+    );
+
+    // And
+    let proof = MerkleProof::create(root.as_ref(), (&both_set).and(&left_set)).build()?;
+    assert_eq!(
+        proof,
+        MerkleProof::create(root.as_ref(), &left_set).build()?
+    );
+
+    // Not
+    let filter = (&left_set).not();
+    assert_eq!(filter.check(&root_hash), FilterAction::Skip);
+    assert_eq!(filter.check(right.repr_hash()), FilterAction::Include);
+
+    // Included subtrees of any filter are included as is
+    let filter = (&left_set).or(filter_fn(|_, depth| match depth {
+        0 => FilterAction::Skip,
+        _ => FilterAction::IncludeSubtree,
+    }));
+    let proof = MerkleProof::create(root.as_ref(), filter).build()?;
+    assert_eq!(proof.cell.repr_hash(), root.repr_hash());
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use std::collections::{HashMap, HashSet};
 use std::hash::BuildHasher;
 
-use super::{make_pruned_branch, FilterAction, MerkleFilter, MerkleProofBuilder};
+use super::proof::check_pruned_branches;
+use super::{
+    make_pruned_branch, FilterAction, FilterContext, FilterNot, MerkleFilter, MerkleProofBuilder,
+};
 use crate::cell::*;
 use crate::error::Error;
 
@@ -346,6 +349,72 @@ impl MerkleUpdate {
         Ok(result)
     }
 
+    /// Checks the structural consistency of this Merkle update.
+    ///
+    /// Verifies hashes and depths of both trees, levels of pruned branches
+    /// and that all unchanged cells of the new tree are presented in the old tree.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.old.hash(0) != &self.old_hash
+            || self.old.depth(0) != self.old_depth
+            || self.new.hash(0) != &self.new_hash
+            || self.new.depth(0) != self.new_depth
+        {
+            return Err(Error::InvalidData);
+        }
+
+        ok!(check_pruned_branches(self.old.as_ref()));
+        ok!(check_pruned_branches(self.new.as_ref()));
+        self.find_old_cells().map(|_| ())
+    }
+
+    /// Combines two consecutive Merkle updates (`A -> B` and `B -> C`)
+    /// into a single update `A -> C`, using an empty cell context.
+    pub fn compose(a_to_b: &Self, b_to_c: &Self) -> Result<Self, Error> {
+        Self::compose_ext(a_to_b, b_to_c, Cell::empty_context())
+    }
+
+    /// Combines two consecutive Merkle updates (`A -> B` and `B -> C`)
+    /// into a single update `A -> C`.
+    ///
+    /// The intermediate tree is not required, all cells are taken from the updates:
+    /// - new cells of `A -> B` which are still used in `C` are moved into the new tree;
+    /// - cells of `A` which are used by `B -> C` are moved into the old tree.
+    pub fn compose_ext(
+        a_to_b: &Self,
+        b_to_c: &Self,
+        context: &dyn CellContext,
+    ) -> Result<Self, Error> {
+        if a_to_b.new_hash != b_to_c.old_hash || a_to_b.new_depth != b_to_c.old_depth {
+            return Err(Error::InvalidData);
+        }
+        ok!(a_to_b.validate());
+        ok!(b_to_c.validate());
+
+        // Old tree is extended with the old cells of the second update.
+        // NOTE: New cells of the first update have different hashes,
+        // so they will never be reached from the old root.
+        let mut old_cells = Default::default();
+        collect_full_cells(&a_to_b.old, &mut old_cells);
+        collect_full_cells(&b_to_c.old, &mut old_cells);
+        let old = ok!(expand_pruned_branches(&a_to_b.old, &old_cells, context));
+
+        // New tree is extended with the new cells of the first update
+        let mut new_cells = Default::default();
+        collect_full_cells(&a_to_b.new, &mut new_cells);
+        let new = ok!(expand_pruned_branches(&b_to_c.new, &new_cells, context));
+
+        let result = Self {
+            old_hash: a_to_b.old_hash,
+            new_hash: b_to_c.new_hash,
+            old_depth: a_to_b.old_depth,
+            new_depth: b_to_c.new_depth,
+            old,
+            new,
+        };
+        ok!(result.validate());
+        Ok(result)
+    }
+
     fn find_old_cells(&self) -> Result<ahash::HashSet<&HashBytes>, Error> {
         let mut visited = ahash::HashSet::default();
         let mut old_cells = ahash::HashSet::default();
@@ -434,6 +503,97 @@ impl MerkleUpdate {
     }
 }
 
+type FullCells = ahash::HashMap<(HashBytes, u8), Cell>;
+
+/// Collects all cells of the Merkle tree which are not pruned branches of it.
+///
+/// Cells are indexed by their original hash and the merkle depth of the parent.
+fn collect_full_cells(root: &Cell, cells: &mut FullCells) {
+    if cells.insert((*root.hash(0), 0), root.clone()).is_some() {
+        return;
+    }
+
+    let mut stack = vec![(root.clone(), root.descriptor().is_merkle() as u8)];
+    while let Some((cell, merkle_depth)) = stack.pop() {
+        for child in cell.references().cloned() {
+            let descriptor = child.descriptor();
+            if descriptor.is_pruned_branch() && descriptor.level_mask().contains(merkle_depth + 1) {
+                continue;
+            }
+
+            let key = (*child.hash(merkle_depth), merkle_depth);
+            if cells.insert(key, child.clone()).is_none() {
+                let child_depth = merkle_depth + descriptor.is_merkle() as u8;
+                stack.push((child, child_depth));
+            }
+        }
+    }
+}
+
+/// Replaces pruned branches of the Merkle tree with the known cells.
+fn expand_pruned_branches(
+    root: &Cell,
+    cells: &FullCells,
+    context: &dyn CellContext,
+) -> Result<Cell, Error> {
+    struct Expander<'a> {
+        cells: &'a FullCells,
+        expanded: FullCells,
+        context: &'a dyn CellContext,
+    }
+
+    impl Expander<'_> {
+        fn run(&mut self, cell: &Cell, merkle_depth: u8) -> Result<Cell, Error> {
+            let key = (*cell.hash(merkle_depth), merkle_depth);
+            if let Some(cell) = self.expanded.get(&key) {
+                return Ok(cell.clone());
+            }
+
+            let descriptor = cell.descriptor();
+            let source = if descriptor.is_pruned_branch()
+                && descriptor.level_mask().contains(merkle_depth + 1)
+            {
+                match self.cells.get(&key) {
+                    Some(cell) => cell,
+                    None => return Ok(cell.clone()),
+                }
+            } else {
+                cell
+            };
+
+            let child_merkle_depth = merkle_depth + source.descriptor().is_merkle() as u8;
+
+            let mut changed = false;
+            let mut children = CellRefsBuilder::default();
+            for child in source.references().cloned() {
+                let expanded = ok!(self.run(&child, child_merkle_depth));
+                changed |= expanded.repr_hash() != child.repr_hash();
+                ok!(children.store_reference(expanded));
+            }
+
+            let result = if changed {
+                let mut builder = CellBuilder::new();
+                builder.set_exotic(source.descriptor().is_exotic());
+                ok!(builder.store_cell_data(source.as_ref()));
+                builder.set_references(children);
+                ok!(builder.build_ext(self.context))
+            } else {
+                source.clone()
+            };
+
+            self.expanded.insert(key, result.clone());
+            Ok(result)
+        }
+    }
+
+    Expander {
+        cells,
+        expanded: Default::default(),
+        context,
+    }
+    .run(root, 0)
+}
+
 /// Helper struct to build a Merkle update.
 pub struct MerkleUpdateBuilder<'a, F> {
     old: &'a DynCell,
@@ -497,7 +657,7 @@ impl<'a: 'b, 'b, 'c: 'a> BuilderImpl<'a, 'b, 'c> {
         where
             S: BuildHasher,
         {
-            fn fill(&mut self, cell: &'a DynCell, mut skip_filter: bool) -> bool {
+            fn fill(&mut self, cell: &'a DynCell, depth: u16, mut skip_filter: bool) -> bool {
                 let repr_hash = cell.repr_hash();
 
                 // Skip visited cells
@@ -518,7 +678,11 @@ impl<'a: 'b, 'b, 'c: 'a> BuilderImpl<'a, 'b, 'c> {
                 let process_children = if skip_filter {
                     true
                 } else {
-                    match self.filter.check(repr_hash) {
+                    let context = FilterContext {
+                        depth,
+                        descriptor: cell.descriptor(),
+                    };
+                    match self.filter.check_ext(repr_hash, &context) {
                         FilterAction::Skip => false,
                         FilterAction::Include => true,
                         FilterAction::IncludeSubtree => {
@@ -531,7 +695,7 @@ impl<'a: 'b, 'b, 'c: 'a> BuilderImpl<'a, 'b, 'c> {
                 let mut result = false;
                 if process_children {
                     for child in cell.references() {
-                        result |= self.fill(child, skip_filter);
+                        result |= self.fill(child, depth + 1, skip_filter);
                     }
 
                     if result {
@@ -543,21 +707,6 @@ impl<'a: 'b, 'b, 'c: 'a> BuilderImpl<'a, 'b, 'c> {
             }
         }
 
-        struct InvertedFilter<F>(F);
-
-        impl<F: MerkleFilter> MerkleFilter for InvertedFilter<F> {
-            #[inline]
-            fn check(&self, cell: &HashBytes) -> FilterAction {
-                if self.0.check(cell) == FilterAction::Skip {
-                    // TODO: check if FilterAction::IncludeSubtree is correct,
-                    // because it is more optimal to just include the new subtree
-                    FilterAction::Include
-                } else {
-                    FilterAction::Skip
-                }
-            }
-        }
-
         let old_hash = self.old.repr_hash();
         let old_depth = self.old.repr_depth();
         let new_hash = self.new.repr_hash();
@@ -580,7 +729,9 @@ impl<'a: 'b, 'b, 'c: 'a> BuilderImpl<'a, 'b, 'c> {
         let (new, pruned_branches) = ok! {
             MerkleProofBuilder::<_>::new(
                 self.new,
-                InvertedFilter(self.filter)
+                // TODO: check if FilterAction::IncludeSubtree is correct,
+                // because it is more optimal to just include the new subtree
+                FilterNot(self.filter)
             )
             .track_pruned_branches()
             .allow_different_root(true)
@@ -596,7 +747,7 @@ impl<'a: 'b, 'b, 'c: 'a> BuilderImpl<'a, 'b, 'c> {
         };
 
         // Find all changed cells in the old cell tree
-        if resolver.fill(self.old, false) {
+        if resolver.fill(self.old, 0, false) {
             resolver.changed_cells.insert(old_hash);
         }
 
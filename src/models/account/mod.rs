@@ -6,9 +6,12 @@ use crate::error::*;
 use crate::num::*;
 
 use crate::models::currency::CurrencyCollection;
-use crate::models::message::IntAddr;
+use crate::models::message::{IntAddr, StdAddr};
 use crate::models::Lazy;
 
+#[cfg(test)]
+mod tests;
+
 /// Amount of unique cells and bits for shard states.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -304,6 +307,38 @@ pub struct Account {
     pub init_code_hash: Option<HashBytes>,
 }
 
+impl Account {
+    /// Creates a new uninitialized account with zero balance.
+    pub fn uninit(address: IntAddr) -> Self {
+        Self {
+            address,
+            storage_stat: StorageInfo::default(),
+            last_trans_lt: 0,
+            balance: CurrencyCollection::ZERO,
+            state: AccountState::Uninit,
+            init_code_hash: None,
+        }
+    }
+
+    /// Returns `true` if the account was deployed with the specified [`StateInit`].
+    ///
+    /// - For uninitialized and active accounts the address is compared with
+    ///   the state init hash. The current state of an active account is not
+    ///   checked, since its data usually changes after deployment;
+    /// - For frozen accounts the stored hash is compared with the state init hash,
+    ///   so it checks whether the state init can unfreeze the account.
+    pub fn matches_state_init(&self, state_init: &StateInit) -> Result<bool, Error> {
+        let hash = ok!(state_init.compute_hash());
+        Ok(match &self.state {
+            AccountState::Uninit | AccountState::Active(_) => match self.address.as_std() {
+                Some(addr) => addr.address == hash,
+                None => false,
+            },
+            AccountState::Frozen(frozen_hash) => *frozen_hash == hash,
+        })
+    }
+}
+
 /// State of an existing account.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -416,6 +451,30 @@ impl StateInit {
     pub const fn reference_count(&self) -> u8 {
         self.code.is_some() as u8 + self.data.is_some() as u8 + !self.libraries.is_empty() as u8
     }
+
+    /// Returns a copy of this state init with the specified code.
+    pub fn with_code(mut self, code: Cell) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Returns a copy of this state init with the specified data.
+    pub fn with_data(mut self, data: Cell) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Computes a representation hash of the serialized state init.
+    pub fn compute_hash(&self) -> Result<HashBytes, Error> {
+        let cell = ok!(CellBuilder::build_from(self));
+        Ok(*cell.repr_hash())
+    }
+
+    /// Computes an address of the contract deployed with this state init
+    /// in the specified workchain.
+    pub fn compute_address(&self, workchain: i8) -> Result<StdAddr, Error> {
+        Ok(StdAddr::new(workchain, ok!(self.compute_hash())))
+    }
 }
 
 impl ExactSize for StateInit {
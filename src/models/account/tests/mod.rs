@@ -0,0 +1,59 @@
+use super::*;
+use crate::prelude::Boc;
+
+#[test]
+fn state_init_address() -> anyhow::Result<()> {
+    let init = Boc::decode(include_bytes!(
+        "internal_message_with_deploy_state_init.boc"
+    ))?
+    .parse::<StateInit>()?;
+
+    let address = init.compute_address(0)?;
+    assert_eq!(
+        address,
+        "0:a4232bb25ca73b09e1bb5200f87548f5a51a2d143d296a5a86b4bf74ec83e662".parse()?
+    );
+    assert_eq!(
+        address.address,
+        *CellBuilder::build_from(&init)?.repr_hash()
+    );
+
+    // Patched data changes the address
+    let new_data = CellBuilder::build_from(0xdeafbeafu32)?;
+    let patched = init.clone().with_data(new_data.clone());
+    assert_eq!(patched.data, Some(new_data));
+    assert_eq!(patched.code, init.code);
+    assert_ne!(patched.compute_address(0)?, address);
+
+    let new_code = Cell::empty_cell();
+    let patched = init.clone().with_code(new_code.clone());
+    assert_eq!(patched.code, Some(new_code));
+    assert_eq!(patched.data, init.data);
+
+    // Uninit account
+    let mut account = Account::uninit(IntAddr::Std(address.clone()));
+    assert_eq!(account.state.status(), AccountStatus::Uninit);
+    assert!(account.balance.is_zero());
+    assert!(account.matches_state_init(&init)?);
+    assert!(!account.matches_state_init(&patched)?);
+
+    // Active account with the changed data
+    account.state = AccountState::Active(patched.clone());
+    assert!(account.matches_state_init(&init)?);
+    assert!(!account.matches_state_init(&patched)?);
+
+    // Active account with the same state at another address
+    let other_address = patched.compute_address(0)?;
+    let other = Account {
+        state: AccountState::Active(init.clone()),
+        ..Account::uninit(IntAddr::Std(other_address))
+    };
+    assert!(!other.matches_state_init(&init)?);
+
+    // Frozen account
+    account.state = AccountState::Frozen(init.compute_hash()?);
+    assert!(account.matches_state_init(&init)?);
+    assert!(!account.matches_state_init(&patched)?);
+
+    Ok(())
+}
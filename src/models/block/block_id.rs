@@ -424,6 +424,11 @@ impl ShardIdent {
             && self.contains_account_prefix(&address.prefix().to_be_bytes())
     }
 
+    /// Returns `true` if the specified address prefix belongs to the current shard.
+    pub const fn contains_prefix(&self, workchain: i32, prefix: u64) -> bool {
+        self.workchain == workchain && self.contains_account_prefix(&prefix.to_be_bytes())
+    }
+
     /// Returns `true` if the specified account could be stored in the current shard.
     pub const fn contains_account(&self, account: &HashBytes) -> bool {
         self.contains_account_prefix(account.first_chunk())
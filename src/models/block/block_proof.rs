@@ -1,8 +1,10 @@
 use crate::cell::*;
 use crate::dict::Dict;
-use crate::error::Error;
+use crate::error::{BlockProofError, Error};
+use crate::merkle::MerkleProof;
 
-use super::{BlockId, BlockSignature};
+use super::{Block, BlockId, BlockSignature};
+use crate::models::config::ValidatorSet;
 #[cfg(feature = "tycho")]
 use crate::models::shard::ConsensusInfo;
 use crate::models::shard::ValidatorBaseInfo;
@@ -20,6 +22,126 @@ pub struct BlockProof {
 
 impl BlockProof {
     const TAG: u8 = 0xc3;
+
+    /// Creates a proof for the specified block.
+    ///
+    /// The Merkle proof contains the block info, the value flow, the state update
+    /// hashes and the masterchain block extra (with the whole config for key blocks).
+    /// Signatures are expected only for masterchain blocks.
+    pub fn create(
+        block_id: &BlockId,
+        block_root: &Cell,
+        signatures: Option<BlockSignatures>,
+    ) -> Result<Self, Error> {
+        if block_root.repr_hash() != &block_id.root_hash
+            || signatures.is_some() && !block_id.is_masterchain()
+        {
+            return Err(Error::InvalidData);
+        }
+
+        let mut usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess).with_subtrees();
+        let block = ok!(usage_tree.track(block_root).parse::<Block>());
+
+        let info = ok!(block.load_info());
+        if info.shard != block_id.shard || info.seqno != block_id.seqno {
+            return Err(Error::InvalidData);
+        }
+        ok!(info.load_prev_ref());
+        ok!(info.load_master_ref());
+        if let Some(prev_vert_ref) = &info.prev_vert_ref {
+            ok!(prev_vert_ref.load());
+        }
+
+        // Include the full value flow
+        usage_tree.add_subtree(block.value_flow.inner().as_ref());
+
+        // Include only the state hashes
+        ok!(block.load_state_update());
+
+        let extra = ok!(block.load_extra());
+        if let Some(custom) = &extra.custom {
+            let mc_extra = ok!(custom.load());
+
+            // Include the whole config for key blocks
+            if let Some(config) = &mc_extra.config {
+                if let Some(root) = config.params.as_dict().root() {
+                    usage_tree.add_subtree(root.as_ref());
+                }
+            }
+        }
+
+        let merkle_proof = ok!(MerkleProof::create(block_root.as_ref(), usage_tree).build());
+
+        Ok(Self {
+            proof_for: *block_id,
+            root: ok!(CellBuilder::build_from(merkle_proof)),
+            signatures,
+        })
+    }
+
+    /// Verifies the block proof using the validator set which signed the block.
+    ///
+    /// Checks that the Merkle proof corresponds to the block id, that all
+    /// signatures are valid and were made by the nodes from the validator set,
+    /// and that the signers weight is greater than 2/3 of the total weight.
+    ///
+    /// Returns the total weight of the signers.
+    pub fn verify(&self, vset: &ValidatorSet) -> Result<u64, BlockProofError> {
+        let merkle_proof = match self.root.parse::<MerkleProof>() {
+            Ok(proof) => proof,
+            Err(e) => return Err(BlockProofError::InvalidMerkleProof(e)),
+        };
+        if let Err(e) = merkle_proof.verify(&self.proof_for.root_hash) {
+            return Err(BlockProofError::InvalidMerkleProof(e));
+        }
+
+        let Some(signatures) = &self.signatures else {
+            return Err(BlockProofError::NoSignatures);
+        };
+
+        // Collect nodes by short id
+        let mut nodes =
+            ahash::HashMap::with_capacity_and_hasher(vset.list.len(), Default::default());
+        for node in &vset.list {
+            let node_id_short = tl_proto::hash(everscale_crypto::tl::PublicKey::Ed25519 {
+                key: node.public_key.as_ref(),
+            });
+            nodes.insert(HashBytes(node_id_short), (node, false));
+        }
+
+        let data = Block::build_data_for_sign(&self.proof_for);
+
+        let mut weight = 0u64;
+        for value in signatures.signatures.values() {
+            let value = match value {
+                Ok(value) => value,
+                Err(e) => return Err(BlockProofError::InvalidSignatures(e)),
+            };
+
+            let node_id_short = value.node_id_short;
+            let Some((node, used)) = nodes.get_mut(&node_id_short) else {
+                return Err(BlockProofError::UnknownValidator(node_id_short));
+            };
+            if std::mem::replace(used, true) {
+                return Err(BlockProofError::DuplicateSignature(node_id_short));
+            }
+            if !node.verify_signature(&data, &value.signature) {
+                return Err(BlockProofError::InvalidSignature(node_id_short));
+            }
+
+            weight = weight.saturating_add(node.weight);
+        }
+
+        let total_weight = vset.total_weight;
+        if (weight as u128) * 3 <= (total_weight as u128) * 2 {
+            return Err(BlockProofError::InsufficientWeight {
+                weight,
+                total_weight,
+            });
+        }
+
+        Ok(weight)
+    }
 }
 
 impl Store for BlockProof {
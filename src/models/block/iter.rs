@@ -0,0 +1,360 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::cell::*;
+use crate::dict::{Dict, DictKey, RawOwnedIter};
+use crate::error::Error;
+
+use crate::models::currency::CurrencyCollection;
+use crate::models::message::{ImportFees, InMsg, OutMsg};
+use crate::models::transaction::{HashUpdate, Transaction};
+use crate::models::Lazy;
+
+use super::{AccountBlock, Block, BlockExtra};
+
+impl Block {
+    /// Gets an iterator over all block transactions, in order by lt.
+    /// The iterator element type is `Result<(account, lt, transaction, hash)>`,
+    /// where `hash` is a representation hash of the transaction cell.
+    ///
+    /// Block content is loaded on the first iteration. Each transaction
+    /// is loaded only when it is yielded.
+    ///
+    /// Errors of the account blocks are yielded as separate items, the iterator
+    /// continues with transactions of the remaining accounts.
+    pub fn iter_transactions(&self) -> BlockTransactionsIter {
+        BlockTransactionsIter {
+            extra: Some(self.extra.clone()),
+            accounts: Vec::new(),
+            heap: BinaryHeap::new(),
+            errors: VecDeque::new(),
+        }
+    }
+
+    /// Gets an iterator over the inbound message descriptions, in order by message hash.
+    /// The iterator element type is `Result<(msg_hash, in_msg)>`.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter_in_msgs(&self) -> BlockInMsgIter {
+        BlockInMsgIter {
+            inner: ExtraDictIter::new(&self.extra, |extra| {
+                Ok(ok!(extra.load_in_msg_description()).dict().clone())
+            }),
+        }
+    }
+
+    /// Gets an iterator over the outbound message descriptions, in order by message hash.
+    /// The iterator element type is `Result<(msg_hash, out_msg)>`.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter_out_msgs(&self) -> BlockOutMsgIter {
+        BlockOutMsgIter {
+            inner: ExtraDictIter::new(&self.extra, |extra| {
+                Ok(ok!(extra.load_out_msg_description()).dict().clone())
+            }),
+        }
+    }
+
+    /// Gets an iterator over the account state hashes before and after this block,
+    /// in order by account id.
+    /// The iterator element type is `Result<(account, state_update)>`.
+    ///
+    /// If the dictionary is invalid, finishes after the first invalid element,
+    /// returning an error.
+    pub fn iter_account_state_updates(&self) -> BlockAccountStateUpdatesIter {
+        BlockAccountStateUpdatesIter {
+            inner: ExtraDictIter::new(&self.extra, load_account_blocks),
+        }
+    }
+}
+
+/// An iterator over the transactions of the block.
+///
+/// This struct is created by the [`iter_transactions`] method on [`Block`].
+/// See its documentation for more.
+///
+/// [`iter_transactions`]: Block::iter_transactions
+#[derive(Clone)]
+pub struct BlockTransactionsIter {
+    extra: Option<Lazy<BlockExtra>>,
+    accounts: Vec<AccountTransactions>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+    errors: VecDeque<Error>,
+}
+
+impl BlockTransactionsIter {
+    fn init(&mut self, extra: &Lazy<BlockExtra>) {
+        let account_blocks = match extra.load().and_then(|extra| load_account_blocks(&extra)) {
+            Ok(account_blocks) => account_blocks,
+            Err(e) => return self.errors.push_back(e),
+        };
+
+        for entry in DictWalker::new(account_blocks) {
+            let (account, (_, account_block)) = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    self.errors.push_back(e);
+                    continue;
+                }
+            };
+
+            self.accounts.push(AccountTransactions {
+                account,
+                transactions: DictWalker::new(account_block.transactions.dict().clone()),
+                next: None,
+            });
+            self.advance(self.accounts.len() - 1);
+        }
+    }
+
+    /// Loads the next transaction of the account, deferring an error if any.
+    fn advance(&mut self, index: usize) {
+        match self.accounts[index].load_next() {
+            Ok(Some(lt)) => self.heap.push(Reverse((lt, index))),
+            Ok(None) => {}
+            Err(e) => self.errors.push_back(e),
+        }
+    }
+}
+
+impl Iterator for BlockTransactionsIter {
+    type Item = Result<(HashBytes, u64, Transaction, HashBytes), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(extra) = self.extra.take() {
+            self.init(&extra);
+        }
+
+        if let Some(e) = self.errors.pop_front() {
+            return Some(Err(e));
+        }
+
+        let Reverse((lt, index)) = self.heap.pop()?;
+        let account = self.accounts[index].account;
+        let tx = self.accounts[index].next.take()?;
+        self.advance(index);
+
+        let hash = *tx.cell.repr_hash();
+        Some(match tx.load() {
+            Ok(tx) => Ok((account, lt, tx, hash)),
+            Err(e) => Err(e),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct AccountTransactions {
+    account: HashBytes,
+    transactions: DictWalker<u64, (CurrencyCollection, Lazy<Transaction>)>,
+    next: Option<Lazy<Transaction>>,
+}
+
+impl AccountTransactions {
+    fn load_next(&mut self) -> Result<Option<u64>, Error> {
+        match self.transactions.next() {
+            Some(Ok((lt, (_, tx)))) => {
+                self.next = Some(tx);
+                Ok(Some(lt))
+            }
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+/// An iterator over the inbound message descriptions of the block.
+///
+/// This struct is created by the [`iter_in_msgs`] method on [`Block`].
+/// See its documentation for more.
+///
+/// [`iter_in_msgs`]: Block::iter_in_msgs
+#[derive(Clone)]
+pub struct BlockInMsgIter {
+    inner: ExtraDictIter<HashBytes, (ImportFees, InMsg)>,
+}
+
+impl Iterator for BlockInMsgIter {
+    type Item = Result<(HashBytes, InMsg), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((hash, (_, in_msg))) => Ok((hash, in_msg)),
+            Err(e) => Err(e),
+        })
+    }
+}
+
+/// An iterator over the outbound message descriptions of the block.
+///
+/// This struct is created by the [`iter_out_msgs`] method on [`Block`].
+/// See its documentation for more.
+///
+/// [`iter_out_msgs`]: Block::iter_out_msgs
+#[derive(Clone)]
+pub struct BlockOutMsgIter {
+    inner: ExtraDictIter<HashBytes, (CurrencyCollection, OutMsg)>,
+}
+
+impl Iterator for BlockOutMsgIter {
+    type Item = Result<(HashBytes, OutMsg), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((hash, (_, out_msg))) => Ok((hash, out_msg)),
+            Err(e) => Err(e),
+        })
+    }
+}
+
+/// An iterator over the account state updates of the block.
+///
+/// This struct is created by the [`iter_account_state_updates`] method on [`Block`].
+/// See its documentation for more.
+///
+/// [`iter_account_state_updates`]: Block::iter_account_state_updates
+#[derive(Clone)]
+pub struct BlockAccountStateUpdatesIter {
+    inner: ExtraDictIter<HashBytes, (CurrencyCollection, AccountBlock)>,
+}
+
+impl Iterator for BlockAccountStateUpdatesIter {
+    type Item = Result<(HashBytes, HashUpdate), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (account, (_, account_block)) = match self.inner.next()? {
+            Ok(entry) => entry,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(match account_block.state_update.load() {
+            Ok(state_update) => Ok((account, state_update)),
+            Err(e) => Err(e),
+        })
+    }
+}
+
+fn load_account_blocks(
+    extra: &BlockExtra,
+) -> Result<Dict<HashBytes, (CurrencyCollection, AccountBlock)>, Error> {
+    Ok(ok!(extra.account_blocks.load()).dict().clone())
+}
+
+type LoadDictFn<K, V> = fn(&BlockExtra) -> Result<Dict<K, V>, Error>;
+
+/// Dictionary iterator which loads the dictionary from the block content
+/// on the first iteration.
+struct ExtraDictIter<K, V> {
+    extra: Option<Lazy<BlockExtra>>,
+    load_dict: LoadDictFn<K, V>,
+    inner: Option<DictWalker<K, V>>,
+}
+
+impl<K, V> Clone for ExtraDictIter<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            extra: self.extra.clone(),
+            load_dict: self.load_dict,
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V> ExtraDictIter<K, V> {
+    fn new(extra: &Lazy<BlockExtra>, load_dict: LoadDictFn<K, V>) -> Self {
+        Self {
+            extra: Some(extra.clone()),
+            load_dict,
+            inner: None,
+        }
+    }
+}
+
+impl<K, V> Iterator for ExtraDictIter<K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(extra) = self.extra.take() {
+            let dict = match extra.load() {
+                Ok(extra) => (self.load_dict)(&extra),
+                Err(e) => Err(e),
+            };
+            match dict {
+                Ok(dict) => self.inner = Some(DictWalker::new(dict)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        self.inner.as_mut()?.next()
+    }
+}
+
+/// Owned dictionary iterator which walks the keys in ascending order.
+struct DictWalker<K, V> {
+    // NOTE: must be declared before `root` since it borrows cells owned by it
+    inner: RawOwnedIter<'static>,
+    root: Arc<Option<Cell>>,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K, V> Clone for DictWalker<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            root: self.root.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K: DictKey, V> DictWalker<K, V> {
+    fn new(dict: Dict<K, V>) -> Self {
+        let root = Arc::new(dict.into_root());
+        let inner = RawOwnedIter::new(root.as_ref(), K::BITS);
+
+        // SAFETY: `root` is never modified and is shared between all clones of
+        // the walker, so the referenced cells live as long as the iterator.
+        let inner =
+            unsafe { std::mem::transmute::<RawOwnedIter<'_>, RawOwnedIter<'static>>(inner) };
+
+        Self {
+            inner,
+            root,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Iterator for DictWalker<K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((key, (cell, range))) => {
+                let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
+                    match range
+                        .apply(&cell)
+                        .and_then(|mut value| V::load_from(&mut value))
+                    {
+                        Ok(value) => return Some(Ok((key, value))),
+                        Err(e) => e,
+                    }
+                } else {
+                    Error::CellUnderflow
+                };
+                Err(self.inner.finish(err))
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
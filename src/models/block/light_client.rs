@@ -0,0 +1,186 @@
+use crate::cell::*;
+use crate::error::{BlockProofError, Error, LightClientError};
+use crate::merkle::MerkleProof;
+
+use super::{Block, BlockId, BlockProof};
+use crate::models::config::{BlockchainConfig, ValidatorSet};
+use crate::models::shard::ShardStateUnsplit;
+
+/// Masterchain light client.
+///
+/// Starts from a trusted zerostate or key block and follows
+/// the chain of key block proofs, verifying each of them
+/// using the validator set from the latest trusted config.
+#[derive(Debug, Clone)]
+pub struct LightClient {
+    block_id: BlockId,
+    config: BlockchainConfig,
+}
+
+impl LightClient {
+    /// Creates a light client from the trusted masterchain block id and config.
+    pub fn new(block_id: BlockId, config: BlockchainConfig) -> Self {
+        Self { block_id, config }
+    }
+
+    /// Creates a light client from the trusted masterchain zerostate.
+    pub fn from_zerostate(block_id: BlockId, state_root: &Cell) -> Result<Self, LightClientError> {
+        if !block_id.is_masterchain() || state_root.repr_hash() != &block_id.root_hash {
+            return Err(LightClientError::BlockIdMismatch);
+        }
+
+        let state = match state_root.parse::<ShardStateUnsplit>() {
+            Ok(state) => state,
+            Err(e) => return Err(LightClientError::InvalidData(e)),
+        };
+        let config = match state.load_custom() {
+            Ok(Some(custom)) => custom.config,
+            Ok(None) => return Err(LightClientError::InvalidData(Error::CellUnderflow)),
+            Err(e) => return Err(LightClientError::InvalidData(e)),
+        };
+
+        Self::with_checked_config(block_id, config)
+    }
+
+    /// Creates a light client from the trusted masterchain key block.
+    pub fn from_key_block(block_id: BlockId, block_root: &Cell) -> Result<Self, LightClientError> {
+        if !block_id.is_masterchain() || block_root.repr_hash() != &block_id.root_hash {
+            return Err(LightClientError::BlockIdMismatch);
+        }
+
+        let config = ok!(load_key_block_config(block_root.as_ref(), &block_id));
+        Self::with_checked_config(block_id, config)
+    }
+
+    /// Returns the latest trusted masterchain block id.
+    pub fn block_id(&self) -> &BlockId {
+        &self.block_id
+    }
+
+    /// Returns the latest trusted blockchain config.
+    pub fn config(&self) -> &BlockchainConfig {
+        &self.config
+    }
+
+    /// Verifies the next key block proof and updates the trusted state.
+    ///
+    /// The proven block must be a masterchain key block which directly
+    /// follows the latest trusted one.
+    pub fn update(&mut self, proof: &BlockProof) -> Result<(), LightClientError> {
+        let block_id = &proof.proof_for;
+        if !block_id.is_masterchain() || block_id.seqno <= self.block_id.seqno {
+            return Err(LightClientError::UnexpectedBlock {
+                seqno: block_id.seqno,
+            });
+        }
+
+        let Some(signatures) = &proof.signatures else {
+            return Err(BlockProofError::NoSignatures.into());
+        };
+
+        let block_root = match proof.root.parse::<MerkleProof>() {
+            Ok(merkle_proof) => match merkle_proof.verify(&block_id.root_hash) {
+                Ok(block_root) => block_root,
+                Err(e) => return Err(BlockProofError::InvalidMerkleProof(e).into()),
+            },
+            Err(e) => return Err(BlockProofError::InvalidMerkleProof(e).into()),
+        };
+
+        // Only a masterchain key block which directly follows
+        // the trusted one can be accepted
+        let info = match block_root
+            .parse::<Block>()
+            .and_then(|block| block.load_info())
+        {
+            Ok(info) => info,
+            Err(e) => return Err(LightClientError::InvalidData(e)),
+        };
+        if info.seqno != block_id.seqno || !info.shard.is_masterchain() {
+            return Err(LightClientError::BlockIdMismatch);
+        }
+        if !info.key_block {
+            return Err(LightClientError::NotKeyBlock);
+        }
+        if info.prev_key_block_seqno != self.block_id.seqno {
+            return Err(LightClientError::UnexpectedPrevKeyBlock {
+                prev_key_block_seqno: info.prev_key_block_seqno,
+            });
+        }
+
+        // Select a validator set which was active at the block creation time
+        let gen_utime = info.gen_utime;
+        let vset = match self.config.get_next_validator_set() {
+            Ok(Some(next)) if gen_utime >= next.utime_since => next,
+            Ok(_) => match self.config.get_current_validator_set() {
+                Ok(current) => current,
+                Err(e) => return Err(LightClientError::InvalidData(e)),
+            },
+            Err(e) => return Err(LightClientError::InvalidData(e)),
+        };
+
+        // Compute the masterchain validator subset which signed the block
+        #[cfg(not(feature = "tycho"))]
+        let shuffle = self.config.get_catchain_config();
+        #[cfg(feature = "tycho")]
+        let shuffle = self.config.get_collation_config();
+        let shuffle = match shuffle {
+            Ok(config) => config.shuffle_mc_validators,
+            Err(e) => return Err(LightClientError::InvalidData(e)),
+        };
+        let cc_seqno = signatures.validator_info.catchain_seqno;
+        let Some((list, hash_short)) = vset.compute_mc_subset(cc_seqno, shuffle) else {
+            return Err(LightClientError::ValidatorSubsetMismatch);
+        };
+        if hash_short != signatures.validator_info.validator_list_hash_short {
+            return Err(LightClientError::ValidatorSubsetMismatch);
+        }
+
+        let subset = ValidatorSet {
+            total_weight: list.iter().map(|item| item.weight).sum(),
+            list,
+            ..vset
+        };
+        if let Err(e) = proof.verify(&subset) {
+            return Err(e.into());
+        }
+
+        let config = ok!(load_key_block_config(block_root.as_ref(), block_id));
+        *self = ok!(Self::with_checked_config(*block_id, config));
+        Ok(())
+    }
+
+    fn with_checked_config(
+        block_id: BlockId,
+        config: BlockchainConfig,
+    ) -> Result<Self, LightClientError> {
+        if let Err(e) = config.get_current_validator_set() {
+            return Err(LightClientError::InvalidData(e));
+        }
+        if let Err(e) = config.get_next_validator_set() {
+            return Err(LightClientError::InvalidData(e));
+        }
+        Ok(Self::new(block_id, config))
+    }
+}
+
+fn load_key_block_config(
+    block_root: &DynCell,
+    block_id: &BlockId,
+) -> Result<BlockchainConfig, LightClientError> {
+    fn load_impl(block_root: &DynCell) -> Result<(u32, bool, Option<BlockchainConfig>), Error> {
+        let block = ok!(block_root.parse::<Block>());
+        let info = ok!(block.load_info());
+        let config = match ok!(block.load_extra()).custom {
+            Some(custom) => ok!(custom.load()).config,
+            None => None,
+        };
+        Ok((info.seqno, info.key_block, config))
+    }
+
+    match load_impl(block_root) {
+        Ok((seqno, _, _)) if seqno != block_id.seqno => Err(LightClientError::BlockIdMismatch),
+        Ok((_, true, Some(config))) => Ok(config),
+        Ok(_) => Err(LightClientError::NotKeyBlock),
+        Err(e) => Err(LightClientError::InvalidData(e)),
+    }
+}
@@ -5,23 +5,28 @@ use std::sync::OnceLock;
 
 use crate::cell::*;
 use crate::dict::Dict;
-use crate::error::Error;
-use crate::merkle::MerkleUpdate;
+use crate::error::{Error, ValueFlowError};
+use crate::merkle::{MerkleProof, MerkleUpdate};
 use crate::num::*;
 use crate::util::*;
 
 use crate::models::currency::CurrencyCollection;
 use crate::models::global_version::GlobalVersion;
+use crate::models::transaction::Transaction;
 use crate::models::Lazy;
 
 pub use self::block_extra::*;
 pub use self::block_id::*;
 pub use self::block_proof::*;
+pub use self::iter::*;
+pub use self::light_client::*;
 pub use self::shard_hashes::*;
 
 mod block_extra;
 mod block_id;
 mod block_proof;
+mod iter;
+mod light_client;
 mod shard_hashes;
 
 #[cfg(test)]
@@ -85,6 +90,185 @@ impl Block {
         data[36..68].copy_from_slice(block_id.file_hash.as_ref());
         data
     }
+
+    /// Creates a Merkle proof for the account transaction with the
+    /// specified logical time in the block with the specified root.
+    ///
+    /// The proof contains block info and the path through account blocks
+    /// to the transaction cell. A proof for a non-existing transaction
+    /// shows its absence.
+    pub fn prove_transaction(
+        block_root: &Cell,
+        account: &HashBytes,
+        lt: u64,
+    ) -> Result<MerkleProof, Error> {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let block = ok!(usage_tree.track(block_root).parse::<Self>());
+        ok!(block.load_info());
+        if let Some(tx) = ok!(Self::find_transaction(&block, account, lt)) {
+            ok!(tx.load());
+        }
+
+        MerkleProof::create(block_root.as_ref(), usage_tree).build()
+    }
+
+    /// Checks the transaction proof against the specified block id.
+    ///
+    /// Returns the transaction or `None` if it doesn't exist in the block.
+    pub fn verify_transaction_proof(
+        proof: &MerkleProof,
+        block_id: &BlockId,
+        account: &HashBytes,
+        lt: u64,
+    ) -> Result<Option<Transaction>, Error> {
+        let block_root = ok!(proof.verify(&block_id.root_hash));
+        let block = ok!(block_root.parse::<Self>());
+
+        let info = ok!(block.load_info());
+        if info.shard != block_id.shard || info.seqno != block_id.seqno {
+            return Err(Error::InvalidData);
+        }
+
+        match ok!(Self::find_transaction(&block, account, lt)) {
+            Some(tx) => tx.load().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Checks that the value flow of the block is consistent with its content.
+    ///
+    /// Imported and exported values are recomputed from the root extras of the
+    /// inbound and outbound message descriptions. Collected fees are recomputed
+    /// from the account blocks, import fees, imported shard fees and created funds,
+    /// excluding copyleft rewards. Shard fees can only be imported by masterchain blocks.
+    ///
+    /// Finally, the balance is checked:
+    ///
+    /// ```text
+    /// from_prev_block + imported + fees_imported + created + minted + recovered
+    ///     == to_next_block + exported + fees_collected
+    /// ```
+    ///
+    /// NOTE: the reference equation also has `burned` on the outgoing side,
+    /// but it is not checked since [`ValueFlow`] only supports the layout
+    /// without burned funds.
+    pub fn validate_value_flow(&self) -> Result<(), ValueFlowError> {
+        fn check(
+            component: &'static str,
+            expected: CurrencyCollection,
+            actual: &CurrencyCollection,
+        ) -> Result<(), ValueFlowError> {
+            if &expected == actual {
+                Ok(())
+            } else {
+                Err(ValueFlowError::ComponentMismatch {
+                    component,
+                    expected,
+                    actual: actual.clone(),
+                })
+            }
+        }
+
+        fn sum(values: &[&CurrencyCollection]) -> Result<CurrencyCollection, Error> {
+            let mut total = CurrencyCollection::ZERO;
+            for value in values {
+                total = ok!(total.checked_add(value));
+            }
+            Ok(total)
+        }
+
+        let load = || -> Result<_, Error> {
+            let info = ok!(self.load_info());
+            let value_flow = ok!(self.load_value_flow());
+            let extra = ok!(self.load_extra());
+            let in_msgs = ok!(extra.load_in_msg_description());
+            let out_msgs = ok!(extra.load_out_msg_description());
+            let account_blocks = ok!(extra.account_blocks.load());
+            let shard_fees = match ok!(extra.load_custom()) {
+                Some(custom) => custom.fees.root_extra().fees.clone(),
+                None => CurrencyCollection::ZERO,
+            };
+            Ok((
+                info,
+                value_flow,
+                in_msgs.root_extra().clone(),
+                out_msgs.root_extra().clone(),
+                account_blocks.root_extra().clone(),
+                shard_fees,
+            ))
+        };
+        let (info, flow, import_fees, exported, tx_fees, shard_fees) =
+            ok!(load().map_err(ValueFlowError::InvalidData));
+
+        ok!(check(
+            "imported",
+            import_fees.value_imported,
+            &flow.imported
+        ));
+        ok!(check("exported", exported, &flow.exported));
+
+        let fees_imported = if info.shard.is_masterchain() {
+            shard_fees
+        } else {
+            CurrencyCollection::ZERO
+        };
+        ok!(check("fees_imported", fees_imported, &flow.fees_imported));
+
+        // NOTE: copyleft rewards are paid from the collected fees
+        let fees_collected = ok!(sum(&[
+            &tx_fees,
+            &CurrencyCollection::from(import_fees.fees_collected),
+            &flow.fees_imported,
+            &flow.created,
+        ])
+        .and_then(|total| {
+            let mut rewards = Tokens::ZERO;
+            for entry in flow.copyleft_rewards.values() {
+                rewards = match rewards.checked_add(ok!(entry)) {
+                    Some(rewards) => rewards,
+                    None => return Err(Error::IntOverflow),
+                };
+            }
+            total.checked_sub(&CurrencyCollection::from(rewards))
+        })
+        .map_err(ValueFlowError::InvalidData));
+        ok!(check(
+            "fees_collected",
+            fees_collected,
+            &flow.fees_collected
+        ));
+
+        let incoming = sum(&[
+            &flow.from_prev_block,
+            &flow.imported,
+            &flow.fees_imported,
+            &flow.created,
+            &flow.minted,
+            &flow.recovered,
+        ]);
+        let outgoing = sum(&[&flow.to_next_block, &flow.exported, &flow.fees_collected]);
+        match (incoming, outgoing) {
+            (Ok(incoming), Ok(outgoing)) if incoming == outgoing => Ok(()),
+            (Ok(incoming), Ok(outgoing)) => Err(ValueFlowError::Unbalanced { incoming, outgoing }),
+            (Err(e), _) | (_, Err(e)) => Err(ValueFlowError::InvalidData(e)),
+        }
+    }
+
+    fn find_transaction(
+        block: &Self,
+        account: &HashBytes,
+        lt: u64,
+    ) -> Result<Option<Lazy<Transaction>>, Error> {
+        let extra = ok!(block.load_extra());
+        let account_blocks = ok!(extra.account_blocks.load());
+        let Some((_, account_block)) = ok!(account_blocks.get(account)) else {
+            return Ok(None);
+        };
+        match ok!(account_block.transactions.get(lt)) {
+            Some((_, tx)) => Ok(Some(tx)),
+            None => Ok(None),
+        }
+    }
 }
 
 impl Store for Block {
@@ -155,6 +155,53 @@ fn shard_block_with_messages() {
     check_block(include_bytes!("simple_shard_block.boc"), None);
 }
 
+#[test]
+fn transaction_proof() {
+    let block_root = Boc::decode(include_bytes!("simple_shard_block.boc")).unwrap();
+    let block = block_root.parse::<Block>().unwrap();
+    let info = block.load_info().unwrap();
+    let block_id = BlockId {
+        shard: info.shard,
+        seqno: info.seqno,
+        root_hash: *block_root.repr_hash(),
+        file_hash: Boc::file_hash(Boc::encode(&block_root)),
+    };
+
+    let account_blocks = block.load_extra().unwrap().account_blocks.load().unwrap();
+    let mut checked = 0;
+    for entry in account_blocks.iter() {
+        let (account, _, account_block) = entry.unwrap();
+        for entry in account_block.transactions.iter() {
+            let (lt, _, tx) = entry.unwrap();
+
+            let proof = Block::prove_transaction(&block_root, &account, lt).unwrap();
+            let proven = Block::verify_transaction_proof(&proof, &block_id, &account, lt)
+                .unwrap()
+                .unwrap();
+            assert_eq!(proven, tx.load().unwrap());
+
+            // Proof of absence
+            let proof = Block::prove_transaction(&block_root, &account, 0).unwrap();
+            assert!(
+                Block::verify_transaction_proof(&proof, &block_id, &account, 0)
+                    .unwrap()
+                    .is_none()
+            );
+
+            // Proof for a different block
+            let mut other_id = block_id;
+            other_id.seqno += 1;
+            assert_eq!(
+                Block::verify_transaction_proof(&proof, &other_id, &account, 0).unwrap_err(),
+                Error::InvalidData
+            );
+
+            checked += 1;
+        }
+    }
+    assert!(checked > 0);
+}
+
 #[test]
 fn parse_block_id() {
     let block_id = BlockId {
@@ -337,6 +384,121 @@ fn proof_for_masterchain_block() {
     assert_eq!(serialize_any(proof).as_ref(), boc.as_ref());
 }
 
+#[test]
+fn verify_block_proof() {
+    use everscale_crypto::ed25519;
+
+    use crate::error::BlockProofError;
+    use crate::models::{Signature, ValidatorDescription, ValidatorSet};
+
+    let boc = Boc::decode(include_bytes!("mc_block_proof.boc")).unwrap();
+    let mut proof = boc.parse::<BlockProof>().unwrap();
+
+    let keys = (0..4u8)
+        .map(|i| ed25519::KeyPair::from(&ed25519::SecretKey::from_bytes([i; 32])))
+        .collect::<Vec<_>>();
+
+    let vset = ValidatorSet {
+        utime_since: 0,
+        utime_until: u32::MAX,
+        main: std::num::NonZeroU16::new(4).unwrap(),
+        total_weight: 40,
+        list: keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| ValidatorDescription {
+                public_key: HashBytes(key.public_key.to_bytes()),
+                weight: 10,
+                adnl_addr: None,
+                mc_seqno_since: 0,
+                prev_total_weight: i as u64 * 10,
+            })
+            .collect(),
+    };
+
+    let data = Block::build_data_for_sign(&proof.proof_for);
+    let sign = |key: &ed25519::KeyPair, data: &[u8]| BlockSignature {
+        node_id_short: HashBytes(tl_proto::hash(everscale_crypto::tl::PublicKey::Ed25519 {
+            key: key.public_key.as_bytes(),
+        })),
+        signature: Signature(key.sign_raw(data)),
+    };
+    let mut with_signatures = |signatures: Vec<BlockSignature>| {
+        let mut dict = Dict::new();
+        for (i, signature) in signatures.into_iter().enumerate() {
+            dict.set(i as u16, signature).unwrap();
+        }
+        proof.signatures.as_mut().unwrap().signatures = dict;
+        proof.clone()
+    };
+
+    // Enough signatures
+    let valid = with_signatures(keys[..3].iter().map(|key| sign(key, &data)).collect());
+    assert_eq!(valid.verify(&vset).unwrap(), 30);
+
+    // Not enough signatures
+    let invalid = with_signatures(keys[..2].iter().map(|key| sign(key, &data)).collect());
+    assert_eq!(
+        invalid.verify(&vset).unwrap_err(),
+        BlockProofError::InsufficientWeight {
+            weight: 20,
+            total_weight: 40
+        }
+    );
+
+    // Invalid signature
+    let mut signatures = keys[..3]
+        .iter()
+        .map(|key| sign(key, &data))
+        .collect::<Vec<_>>();
+    signatures[1] = sign(&keys[1], b"other data");
+    let node_id = signatures[1].node_id_short;
+    let invalid = with_signatures(signatures);
+    assert_eq!(
+        invalid.verify(&vset).unwrap_err(),
+        BlockProofError::InvalidSignature(node_id)
+    );
+
+    // Duplicate signature
+    let mut signatures = keys[..3]
+        .iter()
+        .map(|key| sign(key, &data))
+        .collect::<Vec<_>>();
+    signatures.push(signatures[0].clone());
+    let node_id = signatures[0].node_id_short;
+    let invalid = with_signatures(signatures);
+    assert_eq!(
+        invalid.verify(&vset).unwrap_err(),
+        BlockProofError::DuplicateSignature(node_id)
+    );
+
+    // Unknown validator
+    let unknown = ed25519::KeyPair::from(&ed25519::SecretKey::from_bytes([0xff; 32]));
+    let signature = sign(&unknown, &data);
+    let node_id = signature.node_id_short;
+    let invalid = with_signatures(vec![signature]);
+    assert_eq!(
+        invalid.verify(&vset).unwrap_err(),
+        BlockProofError::UnknownValidator(node_id)
+    );
+
+    // Proof for a different block
+    let mut invalid = valid.clone();
+    invalid.proof_for.root_hash = HashBytes::ZERO;
+    assert!(matches!(
+        invalid.verify(&vset).unwrap_err(),
+        BlockProofError::InvalidMerkleProof(_)
+    ));
+
+    // No signatures
+    let mut invalid = valid;
+    invalid.signatures = None;
+    assert_eq!(
+        invalid.verify(&vset).unwrap_err(),
+        BlockProofError::NoSignatures
+    );
+}
+
 #[test]
 fn proof_for_shardchain_block() {
     let boc = Boc::decode(include_bytes!("shard_block_proof.boc")).unwrap();
@@ -466,3 +628,478 @@ fn block_with_tycho_updates_store_load() {
         }
     );
 }
+
+#[test]
+#[cfg(not(feature = "tycho"))]
+fn light_client_key_blocks() {
+    use everscale_crypto::ed25519;
+
+    use crate::cell::UsageTreeMode;
+    use crate::error::{BlockProofError, LightClientError};
+    use crate::merkle::MerkleProof;
+    use crate::models::{
+        ConfigParam34, ConfigParam36, Signature, ValidatorBaseInfo, ValidatorDescription,
+        ValidatorSet,
+    };
+
+    fn make_keys(seed: u8) -> Vec<ed25519::KeyPair> {
+        (0..4u8)
+            .map(|i| ed25519::KeyPair::from(&ed25519::SecretKey::from_bytes([seed + i; 32])))
+            .collect()
+    }
+
+    fn make_vset(keys: &[ed25519::KeyPair], utime_since: u32) -> ValidatorSet {
+        ValidatorSet {
+            utime_since,
+            utime_until: utime_since + 1000,
+            main: std::num::NonZeroU16::new(keys.len() as u16).unwrap(),
+            total_weight: keys.len() as u64 * 10,
+            list: keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| ValidatorDescription {
+                    public_key: HashBytes(key.public_key.to_bytes()),
+                    weight: 10,
+                    adnl_addr: None,
+                    mc_seqno_since: 0,
+                    prev_total_weight: i as u64 * 10,
+                })
+                .collect(),
+        }
+    }
+
+    let base = Boc::decode(include_bytes!("mc_key_block.boc")).unwrap();
+    let make_key_block = |seqno: u32,
+                          prev_key_block_seqno: u32,
+                          gen_utime: u32,
+                          current: &ValidatorSet,
+                          next: Option<&ValidatorSet>|
+     -> (BlockId, Cell) {
+        let mut block = base.parse::<Block>().unwrap();
+
+        let mut info = block.load_info().unwrap();
+        info.seqno = seqno;
+        info.gen_utime = gen_utime;
+        info.prev_key_block_seqno = prev_key_block_seqno;
+        info.key_block = true;
+        block.info.set(&info).unwrap();
+
+        let mut extra = block.load_extra().unwrap();
+        let custom = extra.custom.as_mut().unwrap();
+        let mut mc_extra = custom.load().unwrap();
+        let config = mc_extra.config.as_mut().unwrap();
+        config.set::<ConfigParam34>(current).unwrap();
+        match next {
+            Some(next) => _ = config.set::<ConfigParam36>(next).unwrap(),
+            None => _ = config.remove(36).unwrap(),
+        }
+        custom.set(&mc_extra).unwrap();
+        block.extra.set(&extra).unwrap();
+
+        let root = CellBuilder::build_from(&block).unwrap();
+        let block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno,
+            root_hash: *root.repr_hash(),
+            file_hash: Boc::file_hash(Boc::encode(&root)),
+        };
+        (block_id, root)
+    };
+
+    let make_proof = |block_id: &BlockId,
+                      root: &Cell,
+                      vset: &ValidatorSet,
+                      signers: &[ed25519::KeyPair],
+                      shuffle: bool|
+     -> BlockProof {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let block = usage_tree.track(root).parse::<Block>().unwrap();
+        block.load_info().unwrap();
+        let extra = block.load_extra().unwrap();
+        let mc_extra = extra.custom.unwrap().load().unwrap();
+        let config_root = CellBuilder::build_from(&mc_extra.config.unwrap().params).unwrap();
+
+        let mut usage_tree = usage_tree.with_subtrees();
+        usage_tree.add_subtree(config_root.reference(0).unwrap());
+        let merkle_proof = MerkleProof::create(root.as_ref(), usage_tree)
+            .build()
+            .unwrap();
+
+        let cc_seqno = 123;
+        let (subset, hash_short) = vset.compute_mc_subset(cc_seqno, shuffle).unwrap();
+        let data = Block::build_data_for_sign(block_id);
+
+        let mut signatures = Dict::new();
+        for (i, key) in signers.iter().enumerate() {
+            signatures
+                .set(
+                    i as u16,
+                    BlockSignature {
+                        node_id_short: HashBytes(tl_proto::hash(
+                            everscale_crypto::tl::PublicKey::Ed25519 {
+                                key: key.public_key.as_bytes(),
+                            },
+                        )),
+                        signature: Signature(key.sign_raw(&data)),
+                    },
+                )
+                .unwrap();
+        }
+
+        BlockProof {
+            proof_for: *block_id,
+            root: CellBuilder::build_from(&merkle_proof).unwrap(),
+            signatures: Some(BlockSignatures {
+                validator_info: ValidatorBaseInfo {
+                    validator_list_hash_short: hash_short,
+                    catchain_seqno: cc_seqno,
+                },
+                signature_count: signers.len() as u32,
+                total_weight: subset.iter().map(|item| item.weight).sum(),
+                signatures,
+            }),
+        }
+    };
+
+    let keys_a = make_keys(0);
+    let keys_b = make_keys(10);
+    let keys_c = make_keys(20);
+    let vset_a = make_vset(&keys_a, 1000);
+    let vset_b = make_vset(&keys_b, 2000);
+    let vset_c = make_vset(&keys_c, 3000);
+
+    // Trusted key block
+    let (block_id, root) = make_key_block(100, 0, 1000, &vset_a, None);
+    let mut client = LightClient::from_key_block(block_id, &root).unwrap();
+    let shuffle = client
+        .config()
+        .get_catchain_config()
+        .unwrap()
+        .shuffle_mc_validators;
+
+    // Key block which skips the next key block
+    let (block_id, root) = make_key_block(300, 200, 1500, &vset_a, Some(&vset_b));
+    let skipped = make_proof(&block_id, &root, &vset_a, &keys_a, shuffle);
+    assert_eq!(
+        client.clone().update(&skipped).unwrap_err(),
+        LightClientError::UnexpectedPrevKeyBlock {
+            prev_key_block_seqno: 200
+        }
+    );
+
+    // Non-key block with a valid chain
+    let (_, root) = make_key_block(200, 100, 1500, &vset_a, Some(&vset_b));
+    let mut block = root.parse::<Block>().unwrap();
+    let mut info = block.load_info().unwrap();
+    info.key_block = false;
+    block.info.set(&info).unwrap();
+    let root = CellBuilder::build_from(&block).unwrap();
+    let block_id = BlockId {
+        shard: ShardIdent::MASTERCHAIN,
+        seqno: 200,
+        root_hash: *root.repr_hash(),
+        file_hash: Boc::file_hash(Boc::encode(&root)),
+    };
+    let non_key = make_proof(&block_id, &root, &vset_a, &keys_a, shuffle);
+    assert_eq!(
+        client.clone().update(&non_key).unwrap_err(),
+        LightClientError::NotKeyBlock
+    );
+
+    // Key block with the next validator set, signed by the current one
+    let (block_id, root) = make_key_block(200, 100, 1500, &vset_a, Some(&vset_b));
+    let proof = make_proof(&block_id, &root, &vset_a, &keys_a[..3], shuffle);
+
+    // Signed by an unknown validator set
+    let invalid = make_proof(&block_id, &root, &vset_b, &keys_b, shuffle);
+    assert_eq!(
+        client.clone().update(&invalid).unwrap_err(),
+        LightClientError::ValidatorSubsetMismatch
+    );
+
+    client.update(&proof).unwrap();
+    assert_eq!(client.block_id(), &block_id);
+    assert_eq!(
+        client.config().get_next_validator_set().unwrap(),
+        Some(vset_b.clone())
+    );
+
+    // Old proofs are rejected
+    assert_eq!(
+        client.clone().update(&proof).unwrap_err(),
+        LightClientError::UnexpectedBlock { seqno: 200 }
+    );
+
+    // Key block after the validator set switch, signed by the next validator set
+    let (block_id, root) = make_key_block(300, 200, 2000, &vset_b, Some(&vset_c));
+    let invalid = make_proof(&block_id, &root, &vset_b, &keys_b[..2], shuffle);
+    assert!(matches!(
+        client.clone().update(&invalid).unwrap_err(),
+        LightClientError::InvalidProof(BlockProofError::InsufficientWeight { .. })
+    ));
+
+    let proof = make_proof(&block_id, &root, &vset_b, &keys_b, shuffle);
+    client.update(&proof).unwrap();
+    assert_eq!(client.block_id(), &block_id);
+    assert_eq!(client.config().get_current_validator_set().unwrap(), vset_b);
+}
+
+#[test]
+fn create_block_proof() {
+    fn check_reference(boc: &[u8]) {
+        let reference = Boc::decode(boc).unwrap().parse::<BlockProof>().unwrap();
+        let merkle_proof = reference.root.parse::<MerkleProof>().unwrap();
+
+        // Proof of the already pruned block must be the same
+        let block_root = Cell::virtualize(merkle_proof.cell.clone());
+        let proof = BlockProof::create(
+            &reference.proof_for,
+            &block_root,
+            reference.signatures.clone(),
+        )
+        .unwrap();
+        assert_eq!(proof.root.as_ref(), reference.root.as_ref());
+    }
+
+    check_reference(include_bytes!("mc_block_proof.boc"));
+    check_reference(include_bytes!("shard_block_proof.boc"));
+
+    // Key block proof must contain the config
+    let data = include_bytes!("mc_key_block.boc");
+    let block_root = Boc::decode(data).unwrap();
+    let info = block_root.parse::<Block>().unwrap().load_info().unwrap();
+    let block_id = BlockId {
+        shard: info.shard,
+        seqno: info.seqno,
+        root_hash: *block_root.repr_hash(),
+        file_hash: Boc::file_hash(data),
+    };
+
+    let proof = BlockProof::create(&block_id, &block_root, None).unwrap();
+    let merkle_proof = proof.root.parse::<MerkleProof>().unwrap();
+    let block = merkle_proof
+        .verify(&block_id.root_hash)
+        .unwrap()
+        .parse::<Block>()
+        .unwrap();
+    block.load_value_flow().unwrap();
+    let mc_extra = block.load_extra().unwrap().custom.unwrap().load().unwrap();
+    let config = mc_extra.config.unwrap();
+    for entry in config.params.as_dict().iter() {
+        entry.unwrap();
+    }
+    config.get_current_validator_set().unwrap();
+
+    // Wrong block id
+    let mut wrong_id = block_id;
+    wrong_id.seqno += 1;
+    assert_eq!(
+        BlockProof::create(&wrong_id, &block_root, None).unwrap_err(),
+        Error::InvalidData
+    );
+}
+
+#[test]
+fn block_iterators() -> anyhow::Result<()> {
+    for boc in [
+        &include_bytes!("simple_shard_block.boc")[..],
+        &include_bytes!("mc_block_with_shards.boc")[..],
+        &include_bytes!("empty_shard_block.boc")[..],
+    ] {
+        let block = Boc::decode(boc)?.parse::<Block>()?;
+        let extra = block.load_extra()?;
+
+        // Transactions
+        let account_blocks = extra.account_blocks.load()?;
+        let mut expected = Vec::new();
+        for entry in account_blocks.iter() {
+            let (account, _, account_block) = entry?;
+            for entry in account_block.transactions.iter() {
+                let (lt, _, tx) = entry?;
+                expected.push((account, lt, tx.load()?, *tx.cell.repr_hash()));
+            }
+        }
+        expected.sort_by_key(|(_, lt, ..)| *lt);
+
+        let transactions = block.iter_transactions().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(transactions, expected);
+        for (account, lt, tx, hash) in &transactions {
+            assert_eq!(&tx.account, account);
+            assert_eq!(&tx.lt, lt);
+            assert_eq!(CellBuilder::build_from(tx)?.repr_hash(), hash);
+        }
+
+        // Inbound messages
+        let in_msgs = block.iter_in_msgs().collect::<Result<Vec<_>, _>>()?;
+        let in_msg_descr = extra.load_in_msg_description()?;
+        assert_eq!(in_msgs.len(), in_msg_descr.iter().count());
+        for (hash, in_msg) in &in_msgs {
+            assert_eq!(in_msg.load_msg_cell()?.repr_hash(), hash);
+        }
+
+        // Outbound messages
+        let out_msgs = block.iter_out_msgs().collect::<Result<Vec<_>, _>>()?;
+        let out_msg_descr = extra.load_out_msg_description()?;
+        assert_eq!(out_msgs.len(), out_msg_descr.iter().count());
+        for (hash, out_msg) in &out_msgs {
+            if let Some(message) = out_msg.load_message()? {
+                assert_eq!(CellBuilder::build_from(message)?.repr_hash(), hash);
+            }
+        }
+
+        // Account state updates
+        let updates = block
+            .iter_account_state_updates()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(updates.len(), account_blocks.iter().count());
+        for (account, update) in updates {
+            let (_, account_block) = account_blocks.get(account)?.unwrap();
+            assert_eq!(account_block.state_update.load()?, update);
+        }
+    }
+
+    // Broken account transactions are reported as separate items
+    let block = Boc::decode(include_bytes!("simple_shard_block.boc"))?.parse::<Block>()?;
+    let mut extra = block.load_extra()?;
+    let mut account_blocks = extra.account_blocks.load()?;
+    let expected = block.iter_transactions().collect::<Result<Vec<_>, _>>()?;
+    assert!(!expected.is_empty());
+
+    let (_, (_, some_account_block)) = account_blocks.dict().get_min(false)?.unwrap();
+    let broken_account = HashBytes([0xff; 32]);
+    let broken = {
+        // Transactions dict with a fork root and invalid children
+        let mut b = CellBuilder::new();
+        b.store_small_uint(5, 4)?;
+        b.store_u256(&broken_account)?;
+        b.store_zeros(2)?;
+        b.store_reference(Cell::empty_cell())?;
+        b.store_reference(Cell::empty_cell())?;
+        CurrencyCollection::ZERO.store_into(&mut b, Cell::empty_context())?;
+        b.store_reference(some_account_block.state_update.cell.clone())?;
+        b.build()?.parse::<AccountBlock>()?
+    };
+    account_blocks.set(broken_account, CurrencyCollection::ZERO, broken)?;
+    extra.account_blocks = Lazy::new(&account_blocks)?;
+
+    let mut block = block.clone();
+    block.extra = Lazy::new(&extra)?;
+
+    let mut transactions = Vec::new();
+    let mut errors = 0;
+    for entry in block.iter_transactions() {
+        match entry {
+            Ok(tx) => transactions.push(tx),
+            Err(_) => errors += 1,
+        }
+    }
+    assert_eq!(transactions, expected);
+    assert_eq!(errors, 1);
+
+    Ok(())
+}
+
+#[test]
+fn validate_value_flow() -> anyhow::Result<()> {
+    for boc in [
+        &include_bytes!("simple_shard_block.boc")[..],
+        &include_bytes!("empty_shard_block.boc")[..],
+        &include_bytes!("mc_simple_block.boc")[..],
+        &include_bytes!("mc_key_block.boc")[..],
+        &include_bytes!("mc_block_with_shards.boc")[..],
+    ] {
+        let block = Boc::decode(boc)?.parse::<Block>()?;
+        block
+            .validate_value_flow()
+            .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    }
+
+    let block = Boc::decode(include_bytes!("simple_shard_block.boc"))?.parse::<Block>()?;
+    let value_flow = block.load_value_flow()?;
+
+    let with_value_flow = |value_flow: &ValueFlow| -> anyhow::Result<Block> {
+        let mut block = block.clone();
+        block.value_flow = Lazy::new(value_flow)?;
+        Ok(block)
+    };
+
+    // Imported value mismatch
+    let invalid = with_value_flow(&ValueFlow {
+        imported: CurrencyCollection::new(1),
+        ..value_flow.clone()
+    })?;
+    assert_eq!(
+        invalid.validate_value_flow().unwrap_err(),
+        ValueFlowError::ComponentMismatch {
+            component: "imported",
+            expected: CurrencyCollection::ZERO,
+            actual: CurrencyCollection::new(1),
+        }
+    );
+
+    // Shard fees are not allowed in shard blocks
+    let invalid = with_value_flow(&ValueFlow {
+        fees_imported: CurrencyCollection::new(1),
+        ..value_flow.clone()
+    })?;
+    assert!(matches!(
+        invalid.validate_value_flow().unwrap_err(),
+        ValueFlowError::ComponentMismatch {
+            component: "fees_imported",
+            ..
+        }
+    ));
+
+    // Collected fees mismatch
+    let invalid = with_value_flow(&ValueFlow {
+        created: value_flow
+            .created
+            .checked_add(&CurrencyCollection::new(1))?,
+        ..value_flow.clone()
+    })?;
+    assert!(matches!(
+        invalid.validate_value_flow().unwrap_err(),
+        ValueFlowError::ComponentMismatch {
+            component: "fees_collected",
+            ..
+        }
+    ));
+
+    // Copyleft rewards are not included into collected fees
+    let mut copyleft_rewards = Dict::new();
+    copyleft_rewards.set(HashBytes([0x11; 32]), Tokens::new(1))?;
+    let invalid = with_value_flow(&ValueFlow {
+        copyleft_rewards,
+        ..value_flow.clone()
+    })?;
+    assert_eq!(
+        invalid.validate_value_flow().unwrap_err(),
+        ValueFlowError::ComponentMismatch {
+            component: "fees_collected",
+            expected: value_flow
+                .fees_collected
+                .checked_sub(&CurrencyCollection::new(1))?,
+            actual: value_flow.fees_collected.clone(),
+        }
+    );
+
+    // Unbalanced
+    let invalid = with_value_flow(&ValueFlow {
+        to_next_block: value_flow
+            .to_next_block
+            .checked_add(&CurrencyCollection::new(1))?,
+        ..value_flow.clone()
+    })?;
+    let incoming = value_flow
+        .from_prev_block
+        .checked_add(&value_flow.created)?;
+    assert_eq!(
+        invalid.validate_value_flow().unwrap_err(),
+        ValueFlowError::Unbalanced {
+            outgoing: incoming.checked_add(&CurrencyCollection::new(1))?,
+            incoming,
+        }
+    );
+
+    Ok(())
+}
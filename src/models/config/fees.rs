@@ -0,0 +1,221 @@
+use crate::cell::CellTreeStats;
+use crate::dict::Dict;
+use crate::error::Error;
+use crate::models::account::StorageUsed;
+use crate::num::Tokens;
+
+use super::params::shift_ceil_price;
+use super::{BlockchainConfigParams, GasLimitsPrices, MsgForwardPrices, StoragePrices};
+
+/// Transaction fees estimator for the specified workchain.
+#[derive(Debug, Clone)]
+pub struct FeeCalculator {
+    is_masterchain: bool,
+    gas_prices: GasLimitsPrices,
+    fwd_prices: MsgForwardPrices,
+    storage_prices: Vec<StoragePrices>,
+}
+
+impl FeeCalculator {
+    /// Creates a fees estimator for the workchain using the prices from the config.
+    ///
+    /// Uses [`ConfigParam18`], [`ConfigParam20`]/[`ConfigParam21`]
+    /// and [`ConfigParam24`]/[`ConfigParam25`].
+    ///
+    /// [`ConfigParam18`]: super::ConfigParam18
+    /// [`ConfigParam20`]: super::ConfigParam20
+    /// [`ConfigParam21`]: super::ConfigParam21
+    /// [`ConfigParam24`]: super::ConfigParam24
+    /// [`ConfigParam25`]: super::ConfigParam25
+    pub fn new(config: &BlockchainConfigParams, workchain: i32) -> Result<Self, Error> {
+        let is_masterchain = workchain == -1;
+
+        let mut storage_prices = Vec::new();
+        for entry in ok!(config.get_storage_prices()).values() {
+            storage_prices.push(ok!(entry));
+        }
+        storage_prices.sort_by_key(|prices| prices.utime_since);
+
+        Ok(Self {
+            is_masterchain,
+            gas_prices: ok!(config.get_gas_prices(is_masterchain)),
+            fwd_prices: ok!(config.get_msg_forward_prices(is_masterchain)),
+            storage_prices,
+        })
+    }
+
+    /// Returns `true` if fees are computed for the masterchain.
+    #[inline]
+    pub fn is_masterchain(&self) -> bool {
+        self.is_masterchain
+    }
+
+    /// Returns gas limits and prices.
+    #[inline]
+    pub fn gas_prices(&self) -> &GasLimitsPrices {
+        &self.gas_prices
+    }
+
+    /// Returns message forwarding prices.
+    #[inline]
+    pub fn fwd_prices(&self) -> &MsgForwardPrices {
+        &self.fwd_prices
+    }
+
+    /// Computes the import fee of an inbound external message.
+    ///
+    /// `stats` must not include the root cell of the message.
+    pub fn compute_import_fee(&self, stats: CellTreeStats) -> Tokens {
+        self.fwd_prices.compute_fwd_fee(stats)
+    }
+
+    /// Computes the forwarding fees of an outbound internal message.
+    ///
+    /// `stats` must not include the root cell of the message.
+    pub fn compute_fwd_fees(&self, stats: CellTreeStats, ihr_disabled: bool) -> FwdFees {
+        let total = self.fwd_prices.compute_fwd_fee(stats);
+        let first_part = self.fwd_prices.get_first_part(total);
+        let ihr_fee = if ihr_disabled {
+            Tokens::ZERO
+        } else {
+            let factor = self.fwd_prices.ihr_price_factor as u128;
+            Tokens::new(total.into_inner().saturating_mul(factor) >> 16)
+        };
+
+        FwdFees {
+            total,
+            first_part,
+            remaining: Tokens::new(total.into_inner() - first_part.into_inner()),
+            ihr_fee,
+        }
+    }
+
+    /// Computes the storage fees for the period since `last_paid` until `now`.
+    ///
+    /// Fees are summed over all storage prices periods within the interval.
+    /// Returns zero if `last_paid` is zero, as the reference implementation does.
+    pub fn compute_storage_fee(&self, stats: CellTreeStats, last_paid: u32, now: u32) -> Tokens {
+        compute_storage_fee_impl(
+            &self.storage_prices,
+            stats,
+            self.is_masterchain,
+            last_paid,
+            now,
+        )
+    }
+
+    /// Converts gas units into tokens.
+    pub fn compute_gas_fee(&self, gas_used: u64) -> Tokens {
+        self.gas_prices.compute_gas_fee(gas_used)
+    }
+
+    /// Computes the amount of gas which can be bought for the specified amount of tokens.
+    ///
+    /// The result is limited by the gas limit of an ordinary transaction.
+    pub fn compute_gas_limit(&self, balance: Tokens) -> u64 {
+        let prices = &self.gas_prices;
+
+        let Some(rest) = balance
+            .into_inner()
+            .checked_sub(prices.flat_gas_price as u128)
+        else {
+            return 0;
+        };
+        if prices.gas_price == 0 {
+            return prices.gas_limit;
+        }
+
+        let gas = rest.saturating_mul(1 << 16) / prices.gas_price as u128;
+        let gas = gas.saturating_add(prices.flat_gas_limit as u128);
+        std::cmp::min(gas, prices.gas_limit as u128) as u64
+    }
+}
+
+/// Computes the storage fees for the period between `from` and `to`.
+///
+/// Fees are summed over all storage prices periods within the interval
+/// and rounded up only once, as the reference implementation does.
+/// The period before the first storage prices entry is free.
+///
+/// Returns zero if `from` is zero, i.e. the storage was never paid.
+pub fn compute_storage_fee_between(
+    prices: &Dict<u32, StoragePrices>,
+    used: &StorageUsed,
+    is_masterchain: bool,
+    from: u32,
+    to: u32,
+) -> Result<Tokens, Error> {
+    let mut items = Vec::new();
+    for entry in prices.values() {
+        items.push(ok!(entry));
+    }
+    items.sort_by_key(|prices| prices.utime_since);
+
+    let stats = CellTreeStats {
+        bit_count: used.bits.into_inner(),
+        cell_count: used.cells.into_inner(),
+    };
+    Ok(compute_storage_fee_impl(
+        &items,
+        stats,
+        is_masterchain,
+        from,
+        to,
+    ))
+}
+
+/// Sums the storage fees over the sorted storage prices periods.
+fn compute_storage_fee_impl(
+    prices: &[StoragePrices],
+    stats: CellTreeStats,
+    is_masterchain: bool,
+    from: u32,
+    to: u32,
+) -> Tokens {
+    let Some(first) = prices.first() else {
+        return Tokens::ZERO;
+    };
+    if from == 0 || to <= from || to <= first.utime_since {
+        return Tokens::ZERO;
+    }
+
+    let mut total = 0u128;
+    let mut upto = std::cmp::max(from, first.utime_since);
+    for (i, item) in prices.iter().enumerate() {
+        if upto >= to {
+            break;
+        }
+
+        let valid_until = match prices.get(i + 1) {
+            Some(next) => std::cmp::min(to, next.utime_since),
+            None => to,
+        };
+        if upto < valid_until {
+            let (bit_price, cell_price) = if is_masterchain {
+                (item.mc_bit_price_ps, item.mc_cell_price_ps)
+            } else {
+                (item.bit_price_ps, item.cell_price_ps)
+            };
+
+            let payment = (stats.cell_count as u128 * cell_price as u128)
+                .saturating_add(stats.bit_count as u128 * bit_price as u128);
+            total = total.saturating_add(payment.saturating_mul((valid_until - upto) as u128));
+        }
+        upto = std::cmp::max(upto, valid_until);
+    }
+
+    Tokens::new(shift_ceil_price(total))
+}
+
+/// Forwarding fees of an outbound internal message.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct FwdFees {
+    /// Total forwarding fee.
+    pub total: Tokens,
+    /// Part of the forwarding fee which is collected in the current block.
+    pub first_part: Tokens,
+    /// Remaining forwarding fee which is stored in the message.
+    pub remaining: Tokens,
+    /// Instant hypercube routing fee.
+    pub ihr_fee: Tokens,
+}
@@ -8,8 +8,10 @@ use crate::num::Tokens;
 use crate::models::currency::ExtraCurrencyCollection;
 use crate::models::global_version::GlobalVersion;
 
+pub use self::fees::*;
 pub use self::params::*;
 
+mod fees;
 mod params;
 
 #[cfg(test)]
@@ -114,6 +116,20 @@ impl BlockchainConfigParams {
         self.set_raw(ConfigParam3::ID, ok!(CellBuilder::build_from(address)))
     }
 
+    /// Returns the burning config.
+    ///
+    /// Uses [`ConfigParam5`].
+    pub fn get_burning_config(&self) -> Result<Option<BurningConfig>, Error> {
+        self.get::<ConfigParam5>()
+    }
+
+    /// Updates the burning config.
+    ///
+    /// Uses [`ConfigParam5`].
+    pub fn set_burning_config(&mut self, config: &BurningConfig) -> Result<bool, Error> {
+        self.set_raw(ConfigParam5::ID, ok!(CellBuilder::build_from(config)))
+    }
+
     /// Returns the lowest supported block version and required capabilities.
     ///
     /// Uses [`ConfigParam8`].
@@ -187,6 +203,20 @@ impl BlockchainConfigParams {
         self.set_raw(ConfigParam12::ID, ok!(CellBuilder::build_from(workchains)))
     }
 
+    /// Returns the complaint pricing.
+    ///
+    /// Uses [`ConfigParam13`].
+    pub fn get_complaint_pricing(&self) -> Result<Option<ComplaintPricing>, Error> {
+        self.get::<ConfigParam13>()
+    }
+
+    /// Updates the complaint pricing.
+    ///
+    /// Uses [`ConfigParam13`].
+    pub fn set_complaint_pricing(&mut self, pricing: &ComplaintPricing) -> Result<bool, Error> {
+        self.set_raw(ConfigParam13::ID, ok!(CellBuilder::build_from(pricing)))
+    }
+
     /// Returns a block creation reward for the specified workchain in tokens.
     ///
     /// Uses [`ConfigParam14`].
@@ -428,6 +458,20 @@ impl BlockchainConfigParams {
         self.set_raw(ConfigParam29::ID, ok!(CellBuilder::build_from(config)))
     }
 
+    /// Returns delector configuration params.
+    ///
+    /// Uses [`ConfigParam30`].
+    pub fn get_delector_params(&self) -> Result<Option<DelectorParams>, Error> {
+        self.get::<ConfigParam30>()
+    }
+
+    /// Updates delector configuration params.
+    ///
+    /// Uses [`ConfigParam30`].
+    pub fn set_delector_params(&mut self, params: &DelectorParams) -> Result<bool, Error> {
+        self.set_raw(ConfigParam30::ID, ok!(CellBuilder::build_from(params)))
+    }
+
     /// Returns a list of fundamental account addresses (in masterchain).
     ///
     /// Uses [`ConfigParam31`].
@@ -490,6 +534,44 @@ impl BlockchainConfigParams {
         }
     }
 
+    /// Returns signed temporary keys of validators.
+    ///
+    /// Uses [`ConfigParam39`].
+    pub fn get_validator_temp_keys(
+        &self,
+    ) -> Result<Option<Dict<HashBytes, ValidatorSignedTempKey>>, Error> {
+        self.get::<ConfigParam39>()
+    }
+
+    /// Updates signed temporary keys of validators.
+    ///
+    /// Uses [`ConfigParam39`].
+    pub fn set_validator_temp_keys(
+        &mut self,
+        keys: &Dict<HashBytes, ValidatorSignedTempKey>,
+    ) -> Result<bool, Error> {
+        self.set_raw(ConfigParam39::ID, ok!(CellBuilder::build_from(keys)))
+    }
+
+    /// Returns the misbehaviour punishment config.
+    ///
+    /// Uses [`ConfigParam40`].
+    pub fn get_misbehaviour_punishment_config(
+        &self,
+    ) -> Result<Option<MisbehaviourPunishmentConfig>, Error> {
+        self.get::<ConfigParam40>()
+    }
+
+    /// Updates the misbehaviour punishment config.
+    ///
+    /// Uses [`ConfigParam40`].
+    pub fn set_misbehaviour_punishment_config(
+        &mut self,
+        config: &MisbehaviourPunishmentConfig,
+    ) -> Result<bool, Error> {
+        self.set_raw(ConfigParam40::ID, ok!(CellBuilder::build_from(config)))
+    }
+
     /// Returns size limits.
     pub fn get_size_limits(&self) -> Result<SizeLimitsConfig, Error> {
         ok!(self.get::<ConfigParam43>()).ok_or(Error::CellUnderflow)
@@ -500,6 +582,100 @@ impl BlockchainConfigParams {
         self.set_raw(ConfigParam43::ID, ok!(CellBuilder::build_from(size_limits)))
     }
 
+    /// Returns a list of suspended addresses.
+    ///
+    /// Uses [`ConfigParam44`].
+    pub fn get_suspended_addresses(&self) -> Result<Option<SuspendedAddressList>, Error> {
+        self.get::<ConfigParam44>()
+    }
+
+    /// Updates a list of suspended addresses.
+    ///
+    /// Uses [`ConfigParam44`].
+    pub fn set_suspended_addresses(
+        &mut self,
+        addresses: &SuspendedAddressList,
+    ) -> Result<bool, Error> {
+        self.set_raw(ConfigParam44::ID, ok!(CellBuilder::build_from(addresses)))
+    }
+
+    /// Returns the precompiled contracts config.
+    ///
+    /// Uses [`ConfigParam45`].
+    pub fn get_precompiled_contracts(&self) -> Result<Option<PrecompiledContractsConfig>, Error> {
+        self.get::<ConfigParam45>()
+    }
+
+    /// Updates the precompiled contracts config.
+    ///
+    /// Uses [`ConfigParam45`].
+    pub fn set_precompiled_contracts(
+        &mut self,
+        config: &PrecompiledContractsConfig,
+    ) -> Result<bool, Error> {
+        self.set_raw(ConfigParam45::ID, ok!(CellBuilder::build_from(config)))
+    }
+
+    /// Returns oracle bridge params for the specified external chain.
+    ///
+    /// Uses [`ConfigParam71`], [`ConfigParam72`] or [`ConfigParam73`].
+    pub fn get_oracle_bridge_params(
+        &self,
+        chain: BridgeChain,
+    ) -> Result<Option<OracleBridgeParams>, Error> {
+        match chain {
+            BridgeChain::Ethereum => self.get::<ConfigParam71>(),
+            BridgeChain::BinanceSmartChain => self.get::<ConfigParam72>(),
+            BridgeChain::Polygon => self.get::<ConfigParam73>(),
+        }
+    }
+
+    /// Updates oracle bridge params for the specified external chain.
+    ///
+    /// Uses [`ConfigParam71`], [`ConfigParam72`] or [`ConfigParam73`].
+    pub fn set_oracle_bridge_params(
+        &mut self,
+        chain: BridgeChain,
+        params: &OracleBridgeParams,
+    ) -> Result<bool, Error> {
+        let id = match chain {
+            BridgeChain::Ethereum => ConfigParam71::ID,
+            BridgeChain::BinanceSmartChain => ConfigParam72::ID,
+            BridgeChain::Polygon => ConfigParam73::ID,
+        };
+        self.set_raw(id, ok!(CellBuilder::build_from(params)))
+    }
+
+    /// Returns jetton bridge params for the specified external chain.
+    ///
+    /// Uses [`ConfigParam79`], [`ConfigParam81`] or [`ConfigParam82`].
+    pub fn get_jetton_bridge_params(
+        &self,
+        chain: BridgeChain,
+    ) -> Result<Option<JettonBridgeParams>, Error> {
+        match chain {
+            BridgeChain::Ethereum => self.get::<ConfigParam79>(),
+            BridgeChain::BinanceSmartChain => self.get::<ConfigParam81>(),
+            BridgeChain::Polygon => self.get::<ConfigParam82>(),
+        }
+    }
+
+    /// Updates jetton bridge params for the specified external chain.
+    ///
+    /// Uses [`ConfigParam79`], [`ConfigParam81`] or [`ConfigParam82`].
+    pub fn set_jetton_bridge_params(
+        &mut self,
+        chain: BridgeChain,
+        params: &JettonBridgeParams,
+    ) -> Result<bool, Error> {
+        let id = match chain {
+            BridgeChain::Ethereum => ConfigParam79::ID,
+            BridgeChain::BinanceSmartChain => ConfigParam81::ID,
+            BridgeChain::Polygon => ConfigParam82::ID,
+        };
+        self.set_raw(id, ok!(CellBuilder::build_from(params)))
+    }
+
     /// Returns `true` if the config contains a param for the specified id.
     pub fn contains<'a, T: KnownConfigParam<'a>>(&'a self) -> Result<bool, Error> {
         self.0.contains_key(T::ID)
@@ -915,6 +1091,12 @@ define_config_params! {
     #[serde(transparent)]
     4 => ConfigParam4(HashBytes),
 
+    /// Burning config.
+    ///
+    /// Contains a [`BurningConfig`].
+    #[serde(transparent)]
+    5 => ConfigParam5(BurningConfig),
+
     /// Mint new price and mint add price (unused).
     6 => ConfigParam6(CellSlice<'a>),
 
@@ -949,7 +1131,10 @@ define_config_params! {
     12 => ConfigParam12(Dict<i32, WorkchainDescription>),
 
     /// Complaint pricing.
-    13 => ConfigParam13(CellSlice<'a>),
+    ///
+    /// Contains a [`ComplaintPricing`].
+    #[serde(transparent)]
+    13 => ConfigParam13(ComplaintPricing),
 
     /// Block creation reward for masterchain and basechain.
     ///
@@ -1041,7 +1226,10 @@ define_config_params! {
     29 => ConfigParam29(ConsensusConfig),
 
     /// Delector configuration params.
-    30 => ConfigParam30(CellSlice<'a>),
+    ///
+    /// Contains a [`DelectorParams`].
+    #[serde(transparent)]
+    30 => ConfigParam30(DelectorParams),
 
     /// Fundamental smartcontract addresses.
     ///
@@ -1085,11 +1273,70 @@ define_config_params! {
     #[serde(transparent)]
     37 => ConfigParam37(ValidatorSet),
 
+    /// Validator signed temporary keys.
+    ///
+    /// Contains a dictionary with [`ValidatorSignedTempKey`] as value.
+    39 => ConfigParam39(Dict<HashBytes, ValidatorSignedTempKey>),
+
+    /// Misbehaviour punishment config.
+    ///
+    /// Contains a [`MisbehaviourPunishmentConfig`].
+    #[serde(transparent)]
+    40 => ConfigParam40(MisbehaviourPunishmentConfig),
+
     /// Size limits.
     ///
     /// Contains a [`SizeLimitsConfig`].
     #[serde(transparent)]
     43 => ConfigParam43(SizeLimitsConfig),
+
+    /// Suspended addresses.
+    ///
+    /// Contains a [`SuspendedAddressList`].
+    #[serde(transparent)]
+    44 => ConfigParam44(SuspendedAddressList),
+
+    /// Precompiled contracts.
+    ///
+    /// Contains a [`PrecompiledContractsConfig`].
+    #[serde(transparent)]
+    45 => ConfigParam45(PrecompiledContractsConfig),
+
+    /// Ethereum oracle bridge params.
+    ///
+    /// Contains an [`OracleBridgeParams`].
+    #[serde(transparent)]
+    71 => ConfigParam71(OracleBridgeParams),
+
+    /// Binance Smart Chain oracle bridge params.
+    ///
+    /// Contains an [`OracleBridgeParams`].
+    #[serde(transparent)]
+    72 => ConfigParam72(OracleBridgeParams),
+
+    /// Polygon oracle bridge params.
+    ///
+    /// Contains an [`OracleBridgeParams`].
+    #[serde(transparent)]
+    73 => ConfigParam73(OracleBridgeParams),
+
+    /// Ethereum jetton bridge params.
+    ///
+    /// Contains a [`JettonBridgeParams`].
+    #[serde(transparent)]
+    79 => ConfigParam79(JettonBridgeParams),
+
+    /// Binance Smart Chain jetton bridge params.
+    ///
+    /// Contains a [`JettonBridgeParams`].
+    #[serde(transparent)]
+    81 => ConfigParam81(JettonBridgeParams),
+
+    /// Polygon jetton bridge params.
+    ///
+    /// Contains a [`JettonBridgeParams`].
+    #[serde(transparent)]
+    82 => ConfigParam82(JettonBridgeParams),
 }
 
 #[cfg(feature = "serde")]
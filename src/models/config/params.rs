@@ -3,13 +3,59 @@ use std::num::{NonZeroU16, NonZeroU32};
 use everscale_crypto::ed25519;
 
 use crate::cell::*;
-use crate::dict::Dict;
+use crate::dict::{Dict, DictKey};
 use crate::error::Error;
 use crate::num::{Tokens, Uint12};
 
 use crate::models::block::ShardIdent;
 use crate::models::{Lazy, Signature};
 
+/// Burning config.
+///
+/// # TLB scheme
+///
+/// ```text
+/// burning_config#01
+///     blackhole_addr:(Maybe bits256)
+///     fee_burn_num:# fee_burn_denom:#
+///     { fee_burn_num <= fee_burn_denom } { fee_burn_denom >= 1 }
+///     = BurningConfig;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#01", validate_with = "Self::is_valid")]
+pub struct BurningConfig {
+    /// Address of the account which receives burned funds.
+    pub blackhole_addr: Option<HashBytes>,
+    /// Numerator of the burned fees part.
+    pub fee_burn_num: u32,
+    /// Denominator of the burned fees part.
+    pub fee_burn_denom: NonZeroU32,
+}
+
+impl BurningConfig {
+    /// Returns `true` if the burned fees part is not greater than one.
+    pub fn is_valid(&self) -> bool {
+        self.fee_burn_num <= self.fee_burn_denom.get()
+    }
+
+    /// Computes the burned part of the fees.
+    pub fn compute_burned_fees(&self, fees: Tokens) -> Result<Tokens, Error> {
+        if self.fee_burn_num == 0 {
+            return Ok(Tokens::ZERO);
+        } else if !self.is_valid() {
+            return Err(Error::InvalidData);
+        }
+
+        // NOTE: `fees * num` can overflow, so the fraction is applied
+        // to the quotient and the remainder separately
+        let fees = fees.into_inner();
+        let num = self.fee_burn_num as u128;
+        let denom = self.fee_burn_denom.get() as u128;
+        Ok(Tokens::new(fees / denom * num + fees % denom * num / denom))
+    }
+}
+
 /// Config voting setup params.
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -258,6 +304,25 @@ impl WorkchainFormatExtended {
     }
 }
 
+/// Complaint pricing.
+///
+/// # TLB scheme
+///
+/// ```text
+/// complaint_prices#1a deposit:Grams bit_price:Grams cell_price:Grams = ComplaintPricing;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#1a")]
+pub struct ComplaintPricing {
+    /// Complaint deposit.
+    pub deposit: Tokens,
+    /// Price per bit of the complaint.
+    pub bit_price: Tokens,
+    /// Price per cell of the complaint.
+    pub cell_price: Tokens,
+}
+
 /// Block creation reward.
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -998,6 +1063,22 @@ impl<'a> Load<'a> for ConsensusConfig {
     }
 }
 
+/// Delector configuration params.
+///
+/// # TLB scheme
+///
+/// ```text
+/// _ delete_delay:uint32 unfreeze_delay:uint32 = DelectorParams;
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DelectorParams {
+    /// Duration in seconds after which a frozen account can be deleted.
+    pub delete_delay: u32,
+    /// Duration in seconds during which a frozen account can be unfrozen.
+    pub unfreeze_delay: u32,
+}
+
 /// Validator set.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -1497,6 +1578,130 @@ impl ValidatorSetPRNG {
     }
 }
 
+/// Validator temporary key.
+///
+/// # TLB scheme
+///
+/// ```text
+/// validator_temp_key#3
+///     adnl_addr:bits256
+///     temp_public_key:SigPubKey
+///     seqno:#
+///     valid_until:uint32
+///     = ValidatorTempKey;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidatorTempKey {
+    /// Validator ADNL address.
+    pub adnl_addr: HashBytes,
+    /// Temporary public key.
+    pub temp_public_key: HashBytes,
+    /// Key seqno.
+    pub seqno: u32,
+    /// Unix timestamp in seconds until which the key is valid.
+    pub valid_until: u32,
+}
+
+impl ValidatorTempKey {
+    const TAG_LEN: u16 = 4;
+    const TAG: u8 = 0x3;
+    const PUBKEY_TAG: u32 = 0x8e81278a;
+}
+
+impl Store for ValidatorTempKey {
+    fn store_into(&self, builder: &mut CellBuilder, _: &dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_small_uint(Self::TAG, Self::TAG_LEN));
+        ok!(builder.store_u256(&self.adnl_addr));
+        ok!(builder.store_u32(Self::PUBKEY_TAG));
+        ok!(builder.store_u256(&self.temp_public_key));
+        ok!(builder.store_u32(self.seqno));
+        builder.store_u32(self.valid_until)
+    }
+}
+
+impl<'a> Load<'a> for ValidatorTempKey {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match slice.load_small_uint(Self::TAG_LEN) {
+            Ok(Self::TAG) => {}
+            Ok(_) => return Err(Error::InvalidTag),
+            Err(e) => return Err(e),
+        }
+
+        let adnl_addr = ok!(slice.load_u256());
+        match slice.load_u32() {
+            Ok(Self::PUBKEY_TAG) => {}
+            Ok(_) => return Err(Error::InvalidTag),
+            Err(e) => return Err(e),
+        }
+
+        Ok(Self {
+            adnl_addr,
+            temp_public_key: ok!(slice.load_u256()),
+            seqno: ok!(slice.load_u32()),
+            valid_until: ok!(slice.load_u32()),
+        })
+    }
+}
+
+/// Signed validator temporary key.
+///
+/// # TLB scheme
+///
+/// ```text
+/// signed_temp_key#4 key:^ValidatorTempKey signature:CryptoSignature = ValidatorSignedTempKey;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[tlb(tag = "#4")]
+pub struct ValidatorSignedTempKey {
+    /// Temporary key info.
+    pub key: Lazy<ValidatorTempKey>,
+    /// Signature of the temporary key info.
+    pub signature: Signature,
+}
+
+/// Misbehaviour punishment config.
+///
+/// # TLB scheme
+///
+/// ```text
+/// misbehaviour_punishment_config_v1#01
+///     default_flat_fine:Grams default_proportional_fine:uint32
+///     severity_flat_mult:uint16 severity_proportional_mult:uint16
+///     unpunishable_interval:uint16
+///     long_interval:uint16 long_flat_mult:uint16 long_proportional_mult:uint16
+///     medium_interval:uint16 medium_flat_mult:uint16 medium_proportional_mult:uint16
+///     = MisbehaviourPunishmentConfig;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#01")]
+pub struct MisbehaviourPunishmentConfig {
+    /// Default flat fine.
+    pub default_flat_fine: Tokens,
+    /// Default proportional fine.
+    pub default_proportional_fine: u32,
+    /// Flat fine multiplier for severe misbehaviour.
+    pub severity_flat_mult: u16,
+    /// Proportional fine multiplier for severe misbehaviour.
+    pub severity_proportional_mult: u16,
+    /// Number of rounds during which the misbehaviour is not punished.
+    pub unpunishable_interval: u16,
+    /// Number of rounds for the long misbehaviour.
+    pub long_interval: u16,
+    /// Flat fine multiplier for the long misbehaviour.
+    pub long_flat_mult: u16,
+    /// Proportional fine multiplier for the long misbehaviour.
+    pub long_proportional_mult: u16,
+    /// Number of rounds for the medium misbehaviour.
+    pub medium_interval: u16,
+    /// Flat fine multiplier for the medium misbehaviour.
+    pub medium_flat_mult: u16,
+    /// Proportional fine multiplier for the medium misbehaviour.
+    pub medium_proportional_mult: u16,
+}
+
 /// size_limits_config_v2#02
 ///     max_msg_bits:uint32
 ///     max_msg_cells:uint32
@@ -1534,7 +1739,313 @@ pub struct SizeLimitsConfig {
     pub defer_out_queue_size_limit: u32,
 }
 
-const fn shift_ceil_price(value: u128) -> u128 {
+/// Suspended addresses.
+///
+/// # TLB scheme
+///
+/// ```text
+/// suspended_address_list#00
+///     addresses:(HashmapE 288 Unit)
+///     suspended_until:uint32
+///     = SuspendedAddressList;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#00")]
+pub struct SuspendedAddressList {
+    /// A set of suspended addresses.
+    #[cfg_attr(feature = "serde", serde(with = "serde_suspended_addresses"))]
+    pub addresses: Dict<SuspendedAddress, ()>,
+    /// Unix timestamp in seconds until which the addresses are suspended.
+    pub suspended_until: u32,
+}
+
+/// Key of the [`SuspendedAddressList`] dictionary.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Store, DictKey)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuspendedAddress {
+    /// Account workchain id.
+    pub workchain: i32,
+    /// Account address.
+    pub address: HashBytes,
+}
+
+#[cfg(feature = "serde")]
+mod serde_suspended_addresses {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(
+        value: &Dict<SuspendedAddress, ()>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, Serialize, SerializeSeq};
+
+        if serializer.is_human_readable() {
+            let mut seq = ok!(serializer.serialize_seq(None));
+            for entry in value.keys() {
+                match entry {
+                    Ok(address) => ok!(seq.serialize_element(&address)),
+                    Err(e) => return Err(Error::custom(e)),
+                }
+            }
+            seq.end()
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Dict<SuspendedAddress, ()>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Deserialize, Error};
+
+        if deserializer.is_human_readable() {
+            let parsed = ok!(Vec::<SuspendedAddress>::deserialize(deserializer));
+
+            let mut res = Dict::new();
+            for item in parsed {
+                ok!(res.set(item, ()).map_err(Error::custom));
+            }
+            Ok(res)
+        } else {
+            Dict::deserialize(deserializer)
+        }
+    }
+}
+
+/// Precompiled contracts config.
+///
+/// # TLB scheme
+///
+/// ```text
+/// precompiled_contracts_config#c0
+///     list:(HashmapE 256 PrecompiledSmc)
+///     = PrecompiledContractsConfig;
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#c0")]
+pub struct PrecompiledContractsConfig {
+    /// Precompiled contracts by code hash.
+    pub list: Dict<HashBytes, PrecompiledSmc>,
+}
+
+/// Precompiled contract info.
+///
+/// # TLB scheme
+///
+/// ```text
+/// precompiled_smc#b0 gas_usage:uint64 = PrecompiledSmc;
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#b0")]
+pub struct PrecompiledSmc {
+    /// Fixed gas usage of the contract.
+    pub gas_usage: u64,
+}
+
+/// External chain of the bridge.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BridgeChain {
+    /// Ethereum.
+    Ethereum,
+    /// Binance Smart Chain.
+    BinanceSmartChain,
+    /// Polygon.
+    Polygon,
+}
+
+/// Oracle bridge params.
+///
+/// # TLB scheme
+///
+/// ```text
+/// oracle_bridge_params#_
+///     bridge_address:bits256
+///     oracle_mutlisig_address:bits256
+///     oracles:(HashmapE 256 uint256)
+///     external_chain_address:bits256
+///     = OracleBridgeParams;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OracleBridgeParams {
+    /// Bridge contract address (in masterchain).
+    pub bridge_address: HashBytes,
+    /// Oracles multisig contract address (in masterchain).
+    pub oracle_multisig_address: HashBytes,
+    /// Oracles public keys by their addresses.
+    pub oracles: Dict<HashBytes, HashBytes>,
+    /// Bridge contract address in the external chain.
+    pub external_chain_address: HashBytes,
+}
+
+/// Jetton bridge params.
+///
+/// # TLB scheme
+///
+/// ```text
+/// jetton_bridge_params_v0#00
+///     bridge_address:bits256
+///     oracles_address:bits256
+///     oracles:(HashmapE 256 uint256)
+///     state_flags:uint8
+///     burn_bridge_fee:Coins
+///     = JettonBridgeParams;
+///
+/// jetton_bridge_params_v1#01
+///     bridge_address:bits256
+///     oracles_address:bits256
+///     oracles:(HashmapE 256 uint256)
+///     state_flags:uint8
+///     prices:^JettonBridgePrices
+///     external_chain_address:bits256
+///     = JettonBridgeParams;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "ty"))]
+pub enum JettonBridgeParams {
+    /// Legacy jetton bridge params.
+    V0(JettonBridgeParamsV0),
+    /// Jetton bridge params with prices.
+    V1(JettonBridgeParamsV1),
+}
+
+impl JettonBridgeParams {
+    const TAG_V0: u8 = 0x00;
+    const TAG_V1: u8 = 0x01;
+
+    /// Returns the bridge contract address (in masterchain).
+    pub fn bridge_address(&self) -> &HashBytes {
+        match self {
+            Self::V0(params) => &params.bridge_address,
+            Self::V1(params) => &params.bridge_address,
+        }
+    }
+
+    /// Returns the oracles multisig contract address (in masterchain).
+    pub fn oracles_address(&self) -> &HashBytes {
+        match self {
+            Self::V0(params) => &params.oracles_address,
+            Self::V1(params) => &params.oracles_address,
+        }
+    }
+
+    /// Returns the oracles public keys by their addresses.
+    pub fn oracles(&self) -> &Dict<HashBytes, HashBytes> {
+        match self {
+            Self::V0(params) => &params.oracles,
+            Self::V1(params) => &params.oracles,
+        }
+    }
+
+    /// Returns the bridge state flags.
+    pub fn state_flags(&self) -> u8 {
+        match self {
+            Self::V0(params) => params.state_flags,
+            Self::V1(params) => params.state_flags,
+        }
+    }
+}
+
+impl Store for JettonBridgeParams {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &dyn CellContext,
+    ) -> Result<(), Error> {
+        match self {
+            Self::V0(params) => {
+                ok!(builder.store_u8(Self::TAG_V0));
+                params.store_into(builder, context)
+            }
+            Self::V1(params) => {
+                ok!(builder.store_u8(Self::TAG_V1));
+                params.store_into(builder, context)
+            }
+        }
+    }
+}
+
+impl<'a> Load<'a> for JettonBridgeParams {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        Ok(match ok!(slice.load_u8()) {
+            Self::TAG_V0 => Self::V0(ok!(JettonBridgeParamsV0::load_from(slice))),
+            Self::TAG_V1 => Self::V1(ok!(JettonBridgeParamsV1::load_from(slice))),
+            _ => return Err(Error::InvalidTag),
+        })
+    }
+}
+
+/// Legacy jetton bridge params.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JettonBridgeParamsV0 {
+    /// Bridge contract address (in masterchain).
+    pub bridge_address: HashBytes,
+    /// Oracles multisig contract address (in masterchain).
+    pub oracles_address: HashBytes,
+    /// Oracles public keys by their addresses.
+    pub oracles: Dict<HashBytes, HashBytes>,
+    /// Bridge state flags.
+    pub state_flags: u8,
+    /// Fee for burning jettons.
+    pub burn_bridge_fee: Tokens,
+}
+
+/// Jetton bridge params with prices.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JettonBridgeParamsV1 {
+    /// Bridge contract address (in masterchain).
+    pub bridge_address: HashBytes,
+    /// Oracles multisig contract address (in masterchain).
+    pub oracles_address: HashBytes,
+    /// Oracles public keys by their addresses.
+    pub oracles: Dict<HashBytes, HashBytes>,
+    /// Bridge state flags.
+    pub state_flags: u8,
+    /// Bridge prices.
+    pub prices: Lazy<JettonBridgePrices>,
+    /// Bridge contract address in the external chain.
+    pub external_chain_address: HashBytes,
+}
+
+/// Jetton bridge prices.
+///
+/// # TLB scheme
+///
+/// ```text
+/// jetton_bridge_prices#_
+///     bridge_burn_fee:Coins bridge_mint_fee:Coins
+///     wallet_min_tons_for_storage:Coins
+///     wallet_gas_consumption:Coins
+///     minter_min_tons_for_storage:Coins
+///     discover_gas_consumption:Coins
+///     = JettonBridgePrices;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JettonBridgePrices {
+    /// Fee for burning jettons.
+    pub bridge_burn_fee: Tokens,
+    /// Fee for minting jettons.
+    pub bridge_mint_fee: Tokens,
+    /// Minimal balance of the jetton wallet.
+    pub wallet_min_tons_for_storage: Tokens,
+    /// Gas consumption of the jetton wallet.
+    pub wallet_gas_consumption: Tokens,
+    /// Minimal balance of the jetton minter.
+    pub minter_min_tons_for_storage: Tokens,
+    /// Gas consumption of the wallet address discovery.
+    pub discover_gas_consumption: Tokens,
+}
+
+pub(crate) const fn shift_ceil_price(value: u128) -> u128 {
     let r = value & 0xffff != 0;
     (value >> 16) + r as u128
 }
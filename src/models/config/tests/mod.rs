@@ -1,11 +1,12 @@
+use std::num::NonZeroU32;
+
 use super::*;
+use crate::models::{Lazy, Signature};
 use crate::prelude::Boc;
 
 #[cfg(not(feature = "tycho"))]
 #[test]
 fn simple_config() {
-    use std::num::NonZeroU32;
-
     let data = Boc::decode(include_bytes!("simple_config.boc")).unwrap();
     let blockchain_config = data.parse::<BlockchainConfig>().unwrap();
 
@@ -442,3 +443,665 @@ fn serde() {
     // Current config
     check_config(include_bytes!("new_config.boc"));
 }
+
+#[test]
+fn extra_config_params() {
+    let data = Boc::decode(include_bytes!("new_config.boc")).unwrap();
+    let mut config = data.parse::<BlockchainConfig>().unwrap();
+
+    // Complaint pricing from the real config
+    let pricing = config.get_complaint_pricing().unwrap().unwrap();
+    assert_eq!(
+        pricing,
+        ComplaintPricing {
+            deposit: Tokens::new(100_000_000_000),
+            bit_price: Tokens::new(1),
+            cell_price: Tokens::new(500),
+        }
+    );
+    assert_eq!(
+        CellBuilder::build_from(&pricing).unwrap().as_ref(),
+        config.get_raw(13).unwrap().unwrap().cell()
+    );
+
+    // Fill all other params
+    let burning = BurningConfig {
+        blackhole_addr: Some(HashBytes([0x11; 32])),
+        fee_burn_num: 1,
+        fee_burn_denom: NonZeroU32::new(2).unwrap(),
+    };
+    config.set_burning_config(&burning).unwrap();
+    assert_eq!(
+        burning.compute_burned_fees(Tokens::new(1001)).unwrap(),
+        Tokens::new(500)
+    );
+    let full_burning = BurningConfig {
+        blackhole_addr: None,
+        fee_burn_num: u32::MAX,
+        fee_burn_denom: NonZeroU32::new(u32::MAX).unwrap(),
+    };
+    assert_eq!(
+        full_burning.compute_burned_fees(Tokens::MAX).unwrap(),
+        Tokens::MAX
+    );
+
+    let delector = DelectorParams {
+        delete_delay: 86400,
+        unfreeze_delay: 3600,
+    };
+    config.set_delector_params(&delector).unwrap();
+
+    let mut temp_keys = Dict::new();
+    temp_keys
+        .set(
+            HashBytes([0x22; 32]),
+            ValidatorSignedTempKey {
+                key: Lazy::new(&ValidatorTempKey {
+                    adnl_addr: HashBytes([0x33; 32]),
+                    temp_public_key: HashBytes([0x44; 32]),
+                    seqno: 10,
+                    valid_until: 1700000000,
+                })
+                .unwrap(),
+                signature: Signature([0x55; 64]),
+            },
+        )
+        .unwrap();
+    config.set_validator_temp_keys(&temp_keys).unwrap();
+
+    let punishment = MisbehaviourPunishmentConfig {
+        default_flat_fine: Tokens::new(101_000_000_000),
+        default_proportional_fine: 1 << 28,
+        severity_flat_mult: 256,
+        severity_proportional_mult: 256,
+        unpunishable_interval: 0,
+        long_interval: 10,
+        long_flat_mult: 1280,
+        long_proportional_mult: 1280,
+        medium_interval: 2,
+        medium_flat_mult: 768,
+        medium_proportional_mult: 768,
+    };
+    config
+        .set_misbehaviour_punishment_config(&punishment)
+        .unwrap();
+
+    let mut suspended = SuspendedAddressList {
+        addresses: Dict::new(),
+        suspended_until: 1700000000,
+    };
+    suspended
+        .addresses
+        .set(
+            SuspendedAddress {
+                workchain: 0,
+                address: HashBytes([0x66; 32]),
+            },
+            (),
+        )
+        .unwrap();
+    config.set_suspended_addresses(&suspended).unwrap();
+
+    let mut precompiled = PrecompiledContractsConfig::default();
+    precompiled
+        .list
+        .set(HashBytes([0x77; 32]), PrecompiledSmc { gas_usage: 1000 })
+        .unwrap();
+    config.set_precompiled_contracts(&precompiled).unwrap();
+
+    let mut oracles = Dict::new();
+    oracles
+        .set(HashBytes([0x88; 32]), HashBytes([0x99; 32]))
+        .unwrap();
+
+    let oracle_bridge = OracleBridgeParams {
+        bridge_address: HashBytes([0xaa; 32]),
+        oracle_multisig_address: HashBytes([0xbb; 32]),
+        oracles: oracles.clone(),
+        external_chain_address: HashBytes([0xcc; 32]),
+    };
+    let jetton_bridge_v0 = JettonBridgeParams::V0(JettonBridgeParamsV0 {
+        bridge_address: HashBytes([0xaa; 32]),
+        oracles_address: HashBytes([0xbb; 32]),
+        oracles: oracles.clone(),
+        state_flags: 0,
+        burn_bridge_fee: Tokens::new(1_000_000_000),
+    });
+    let jetton_bridge_v1 = JettonBridgeParams::V1(JettonBridgeParamsV1 {
+        bridge_address: HashBytes([0xaa; 32]),
+        oracles_address: HashBytes([0xbb; 32]),
+        oracles,
+        state_flags: 1,
+        prices: Lazy::new(&JettonBridgePrices {
+            bridge_burn_fee: Tokens::new(1),
+            bridge_mint_fee: Tokens::new(2),
+            wallet_min_tons_for_storage: Tokens::new(3),
+            wallet_gas_consumption: Tokens::new(4),
+            minter_min_tons_for_storage: Tokens::new(5),
+            discover_gas_consumption: Tokens::new(6),
+        })
+        .unwrap(),
+        external_chain_address: HashBytes([0xcc; 32]),
+    });
+
+    for chain in [
+        BridgeChain::Ethereum,
+        BridgeChain::BinanceSmartChain,
+        BridgeChain::Polygon,
+    ] {
+        config
+            .set_oracle_bridge_params(chain, &oracle_bridge)
+            .unwrap();
+        let jetton_bridge = match chain {
+            BridgeChain::Polygon => &jetton_bridge_v0,
+            _ => &jetton_bridge_v1,
+        };
+        config
+            .set_jetton_bridge_params(chain, jetton_bridge)
+            .unwrap();
+    }
+
+    // Check round trip
+    let cell = CellBuilder::build_from(&config).unwrap();
+    let parsed = cell.parse::<BlockchainConfig>().unwrap();
+    assert_eq!(parsed, config);
+
+    assert_eq!(parsed.get_burning_config().unwrap(), Some(burning));
+    assert_eq!(parsed.get_complaint_pricing().unwrap(), Some(pricing));
+    assert_eq!(parsed.get_delector_params().unwrap(), Some(delector));
+    assert_eq!(parsed.get_validator_temp_keys().unwrap(), Some(temp_keys));
+    assert_eq!(
+        parsed.get_misbehaviour_punishment_config().unwrap(),
+        Some(punishment)
+    );
+    assert_eq!(parsed.get_suspended_addresses().unwrap(), Some(suspended));
+    assert_eq!(
+        parsed.get_precompiled_contracts().unwrap(),
+        Some(precompiled)
+    );
+    for (chain, jetton_bridge) in [
+        (BridgeChain::Ethereum, &jetton_bridge_v1),
+        (BridgeChain::BinanceSmartChain, &jetton_bridge_v1),
+        (BridgeChain::Polygon, &jetton_bridge_v0),
+    ] {
+        assert_eq!(
+            parsed.get_oracle_bridge_params(chain).unwrap().as_ref(),
+            Some(&oracle_bridge)
+        );
+        assert_eq!(
+            parsed.get_jetton_bridge_params(chain).unwrap().as_ref(),
+            Some(jetton_bridge)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string_pretty(&config).unwrap();
+        let from_json: BlockchainConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, config);
+
+        // Suspended addresses are not serialized as an opaque cell
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["params"]["44"]["addresses"][0]["workchain"],
+            serde_json::json!(0)
+        );
+    }
+}
+
+#[test]
+fn extra_config_params_layout() {
+    // NOTE: reference cells are assembled field by field from the TLB schemes,
+    // independently of the `Store`/`Load` implementations.
+    fn store_coins(b: &mut CellBuilder, value: u64) {
+        let bytes = value.to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        b.store_small_uint((8 - skip) as u8, 4).unwrap();
+        b.store_raw(&bytes[skip..], (8 - skip) as u16 * 8).unwrap();
+    }
+
+    fn store_dict<K, V>(b: &mut CellBuilder, dict: &Dict<K, V>) {
+        match dict.root() {
+            Some(root) => {
+                b.store_bit_one().unwrap();
+                b.store_reference(root.clone()).unwrap();
+            }
+            None => b.store_bit_zero().unwrap(),
+        }
+    }
+
+    fn check_layout<T>(raw: &Cell, expected: &T)
+    where
+        T: Store + for<'a> Load<'a> + Eq + std::fmt::Debug,
+    {
+        assert_eq!(&raw.parse::<T>().unwrap(), expected);
+        assert_eq!(
+            CellBuilder::build_from(expected).unwrap().as_ref(),
+            raw.as_ref()
+        );
+    }
+
+    let mut config = Boc::decode(include_bytes!("new_config.boc"))
+        .unwrap()
+        .parse::<BlockchainConfig>()
+        .unwrap();
+
+    // Param 5
+    let burning = BurningConfig {
+        blackhole_addr: Some(HashBytes([0x11; 32])),
+        fee_burn_num: 1,
+        fee_burn_denom: NonZeroU32::new(2).unwrap(),
+    };
+    let mut b = CellBuilder::new();
+    b.store_u8(0x01).unwrap();
+    b.store_bit_one().unwrap();
+    b.store_u256(&HashBytes([0x11; 32])).unwrap();
+    b.store_u32(1).unwrap();
+    b.store_u32(2).unwrap();
+    let raw = b.build().unwrap();
+    check_layout(&raw, &burning);
+    config.set_raw(5, raw).unwrap();
+
+    // Param 30
+    let delector = DelectorParams {
+        delete_delay: 86400,
+        unfreeze_delay: 3600,
+    };
+    let mut b = CellBuilder::new();
+    b.store_u32(86400).unwrap();
+    b.store_u32(3600).unwrap();
+    let raw = b.build().unwrap();
+    check_layout(&raw, &delector);
+    config.set_raw(30, raw).unwrap();
+
+    // Param 39
+    let temp_key = ValidatorTempKey {
+        adnl_addr: HashBytes([0x22; 32]),
+        temp_public_key: HashBytes([0x33; 32]),
+        seqno: 7,
+        valid_until: 1700000000,
+    };
+    let mut b = CellBuilder::new();
+    b.store_small_uint(0x3, 4).unwrap();
+    b.store_u256(&HashBytes([0x22; 32])).unwrap();
+    b.store_u32(0x8e81278a).unwrap();
+    b.store_u256(&HashBytes([0x33; 32])).unwrap();
+    b.store_u32(7).unwrap();
+    b.store_u32(1700000000).unwrap();
+    let raw_temp_key = b.build().unwrap();
+    check_layout(&raw_temp_key, &temp_key);
+
+    let signed_temp_key = ValidatorSignedTempKey {
+        key: Lazy::new(&temp_key).unwrap(),
+        signature: Signature([0x44; 64]),
+    };
+    let mut b = CellBuilder::new();
+    b.store_small_uint(0x4, 4).unwrap();
+    b.store_reference(raw_temp_key).unwrap();
+    b.store_small_uint(0x5, 4).unwrap();
+    b.store_raw(&[0x44; 64], 512).unwrap();
+    let raw = b.build().unwrap();
+    check_layout(&raw, &signed_temp_key);
+
+    let mut temp_keys = Dict::<HashBytes, ValidatorSignedTempKey>::new();
+    temp_keys
+        .set(HashBytes([0x55; 32]), signed_temp_key)
+        .unwrap();
+    let mut b = CellBuilder::new();
+    store_dict(&mut b, &temp_keys);
+    config.set_raw(39, b.build().unwrap()).unwrap();
+
+    // Param 40
+    let punishment = MisbehaviourPunishmentConfig {
+        default_flat_fine: Tokens::new(101_000_000_000),
+        default_proportional_fine: 1 << 24,
+        severity_flat_mult: 24,
+        severity_proportional_mult: 5,
+        unpunishable_interval: 40,
+        long_interval: 1800,
+        long_flat_mult: 24,
+        long_proportional_mult: 8,
+        medium_interval: 500,
+        medium_flat_mult: 8,
+        medium_proportional_mult: 4,
+    };
+    let mut b = CellBuilder::new();
+    b.store_u8(0x01).unwrap();
+    store_coins(&mut b, 101_000_000_000);
+    b.store_u32(1 << 24).unwrap();
+    for value in [24, 5, 40, 1800, 24, 8, 500, 8, 4] {
+        b.store_u16(value).unwrap();
+    }
+    let raw = b.build().unwrap();
+    check_layout(&raw, &punishment);
+    config.set_raw(40, raw).unwrap();
+
+    // Param 44
+    let mut suspended = SuspendedAddressList {
+        addresses: Dict::new(),
+        suspended_until: 1700000000,
+    };
+    suspended
+        .addresses
+        .set(
+            SuspendedAddress {
+                workchain: 0,
+                address: HashBytes([0x66; 32]),
+            },
+            (),
+        )
+        .unwrap();
+    let mut key = CellBuilder::new();
+    key.store_u32(0).unwrap();
+    key.store_u256(&HashBytes([0x66; 32])).unwrap();
+    let key = key.build().unwrap();
+    let mut addresses = crate::dict::RawDict::<288>::new();
+    addresses.set(key.as_slice().unwrap(), ()).unwrap();
+    let mut b = CellBuilder::new();
+    b.store_u8(0x00).unwrap();
+    addresses.store_into(&mut b, Cell::empty_context()).unwrap();
+    b.store_u32(1700000000).unwrap();
+    let raw = b.build().unwrap();
+    check_layout(&raw, &suspended);
+    config.set_raw(44, raw).unwrap();
+
+    // Param 45
+    let mut precompiled = PrecompiledContractsConfig::default();
+    precompiled
+        .list
+        .set(HashBytes([0x77; 32]), PrecompiledSmc { gas_usage: 1000 })
+        .unwrap();
+    let mut list = Dict::<HashBytes, (u8, u64)>::new();
+    list.set(HashBytes([0x77; 32]), (0xb0, 1000)).unwrap();
+    let mut b = CellBuilder::new();
+    b.store_u8(0xc0).unwrap();
+    store_dict(&mut b, &list);
+    let raw = b.build().unwrap();
+    check_layout(&raw, &precompiled);
+    config.set_raw(45, raw).unwrap();
+
+    // Params 71-73
+    let mut oracles = Dict::<HashBytes, HashBytes>::new();
+    oracles
+        .set(HashBytes([0x88; 32]), HashBytes([0x99; 32]))
+        .unwrap();
+
+    let oracle_bridge = OracleBridgeParams {
+        bridge_address: HashBytes([0xaa; 32]),
+        oracle_multisig_address: HashBytes([0xbb; 32]),
+        oracles: oracles.clone(),
+        external_chain_address: HashBytes([0xcc; 32]),
+    };
+    let mut b = CellBuilder::new();
+    b.store_u256(&HashBytes([0xaa; 32])).unwrap();
+    b.store_u256(&HashBytes([0xbb; 32])).unwrap();
+    store_dict(&mut b, &oracles);
+    b.store_u256(&HashBytes([0xcc; 32])).unwrap();
+    let raw = b.build().unwrap();
+    check_layout(&raw, &oracle_bridge);
+    for id in [71, 72, 73] {
+        config.set_raw(id, raw.clone()).unwrap();
+    }
+
+    // Param 79 and 81
+    let prices = JettonBridgePrices {
+        bridge_burn_fee: Tokens::new(1),
+        bridge_mint_fee: Tokens::new(2),
+        wallet_min_tons_for_storage: Tokens::new(3),
+        wallet_gas_consumption: Tokens::new(4),
+        minter_min_tons_for_storage: Tokens::new(5),
+        discover_gas_consumption: Tokens::new(6),
+    };
+    let mut b = CellBuilder::new();
+    for value in 1..=6 {
+        store_coins(&mut b, value);
+    }
+    let raw_prices = b.build().unwrap();
+    check_layout(&raw_prices, &prices);
+
+    let jetton_bridge_v1 = JettonBridgeParams::V1(JettonBridgeParamsV1 {
+        bridge_address: HashBytes([0xaa; 32]),
+        oracles_address: HashBytes([0xbb; 32]),
+        oracles: oracles.clone(),
+        state_flags: 1,
+        prices: Lazy::new(&prices).unwrap(),
+        external_chain_address: HashBytes([0xcc; 32]),
+    });
+    let mut b = CellBuilder::new();
+    b.store_u8(0x01).unwrap();
+    b.store_u256(&HashBytes([0xaa; 32])).unwrap();
+    b.store_u256(&HashBytes([0xbb; 32])).unwrap();
+    store_dict(&mut b, &oracles);
+    b.store_u8(1).unwrap();
+    b.store_reference(raw_prices).unwrap();
+    b.store_u256(&HashBytes([0xcc; 32])).unwrap();
+    let raw = b.build().unwrap();
+    check_layout(&raw, &jetton_bridge_v1);
+    for id in [79, 81] {
+        config.set_raw(id, raw.clone()).unwrap();
+    }
+
+    // Param 82
+    let jetton_bridge_v0 = JettonBridgeParams::V0(JettonBridgeParamsV0 {
+        bridge_address: HashBytes([0xaa; 32]),
+        oracles_address: HashBytes([0xbb; 32]),
+        oracles: oracles.clone(),
+        state_flags: 0,
+        burn_bridge_fee: Tokens::new(1_000_000_000),
+    });
+    let mut b = CellBuilder::new();
+    b.store_u8(0x00).unwrap();
+    b.store_u256(&HashBytes([0xaa; 32])).unwrap();
+    b.store_u256(&HashBytes([0xbb; 32])).unwrap();
+    store_dict(&mut b, &oracles);
+    b.store_u8(0).unwrap();
+    store_coins(&mut b, 1_000_000_000);
+    let raw = b.build().unwrap();
+    check_layout(&raw, &jetton_bridge_v0);
+    config.set_raw(82, raw).unwrap();
+
+    // Typed getters read the reference layouts
+    assert_eq!(config.get_burning_config().unwrap(), Some(burning));
+    assert_eq!(config.get_delector_params().unwrap(), Some(delector));
+    assert_eq!(config.get_validator_temp_keys().unwrap(), Some(temp_keys));
+    assert_eq!(
+        config.get_misbehaviour_punishment_config().unwrap(),
+        Some(punishment)
+    );
+    assert_eq!(config.get_suspended_addresses().unwrap(), Some(suspended));
+    assert_eq!(
+        config.get_precompiled_contracts().unwrap(),
+        Some(precompiled)
+    );
+    for (chain, jetton_bridge) in [
+        (BridgeChain::Ethereum, &jetton_bridge_v1),
+        (BridgeChain::BinanceSmartChain, &jetton_bridge_v1),
+        (BridgeChain::Polygon, &jetton_bridge_v0),
+    ] {
+        assert_eq!(
+            config.get_oracle_bridge_params(chain).unwrap().as_ref(),
+            Some(&oracle_bridge)
+        );
+        assert_eq!(
+            config.get_jetton_bridge_params(chain).unwrap().as_ref(),
+            Some(jetton_bridge)
+        );
+    }
+}
+
+#[test]
+fn fee_calculator() {
+    let data = Boc::decode(include_bytes!("new_config.boc")).unwrap();
+    let config = data.parse::<BlockchainConfig>().unwrap();
+
+    for workchain in [-1, 0] {
+        let is_masterchain = workchain == -1;
+        let fees = FeeCalculator::new(&config, workchain).unwrap();
+        assert_eq!(fees.is_masterchain(), is_masterchain);
+
+        // Forwarding fees
+        let fwd_prices = config.get_msg_forward_prices(is_masterchain).unwrap();
+        let stats = CellTreeStats {
+            bit_count: 1000,
+            cell_count: 3,
+        };
+        let total = fwd_prices.compute_fwd_fee(stats);
+        assert_eq!(fees.compute_import_fee(stats), total);
+
+        let fwd_fees = fees.compute_fwd_fees(stats, true);
+        assert_eq!(fwd_fees.total, total);
+        assert_eq!(fwd_fees.first_part, fwd_prices.get_first_part(total));
+        assert_eq!(
+            fwd_fees.first_part.into_inner() + fwd_fees.remaining.into_inner(),
+            total.into_inner()
+        );
+        assert_eq!(fwd_fees.ihr_fee, Tokens::ZERO);
+        let fwd_fees = fees.compute_fwd_fees(stats, false);
+        assert_eq!(
+            fwd_fees.ihr_fee,
+            Tokens::new((total.into_inner() * fwd_prices.ihr_price_factor as u128) >> 16)
+        );
+
+        // Gas
+        let gas_prices = config.get_gas_prices(is_masterchain).unwrap();
+        assert_eq!(
+            fees.compute_gas_fee(0),
+            Tokens::new(gas_prices.flat_gas_price as _)
+        );
+        assert_eq!(
+            fees.compute_gas_fee(gas_prices.flat_gas_limit),
+            Tokens::new(gas_prices.flat_gas_price as _)
+        );
+        for gas in [gas_prices.flat_gas_limit + 1, 10_000, 123_456] {
+            let fee = fees.compute_gas_fee(gas);
+            assert_eq!(fees.compute_gas_limit(fee), gas);
+        }
+        assert_eq!(fees.compute_gas_limit(Tokens::ZERO), 0);
+        assert_eq!(fees.compute_gas_limit(Tokens::MAX), gas_prices.gas_limit);
+
+        // Storage
+        let storage_prices = config.get_storage_prices().unwrap();
+        let (_, latest) = storage_prices.get_max(false).unwrap().unwrap();
+        let last_paid = latest.utime_since + 1;
+        let now = last_paid + 1000;
+        assert_eq!(
+            fees.compute_storage_fee(stats, last_paid, now),
+            latest.compute_storage_fee(is_masterchain, 1000, stats)
+        );
+        assert_eq!(fees.compute_storage_fee(stats, 0, now), Tokens::ZERO);
+        assert_eq!(fees.compute_storage_fee(stats, now, now), Tokens::ZERO);
+        assert_eq!(fees.compute_storage_fee(stats, now + 10, now), Tokens::ZERO);
+    }
+
+    // Storage fees across several periods
+    let mut config = BlockchainConfig::new_empty(HashBytes::ZERO);
+    let prices = [(100, 1 << 16, 2 << 16), (200, 3 << 16, 4 << 16)].map(|(since, bit, cell)| {
+        StoragePrices {
+            utime_since: since,
+            bit_price_ps: bit,
+            cell_price_ps: cell,
+            mc_bit_price_ps: bit * 10,
+            mc_cell_price_ps: cell * 10,
+        }
+    });
+    config.set_storage_prices(&prices).unwrap();
+    config
+        .set_gas_prices(false, &GasLimitsPrices::default())
+        .unwrap();
+    config
+        .set_msg_forward_prices(
+            false,
+            &MsgForwardPrices {
+                lump_price: 0,
+                bit_price: 0,
+                cell_price: 0,
+                ihr_price_factor: 0,
+                first_frac: 0,
+                next_frac: 0,
+            },
+        )
+        .unwrap();
+
+    let fees = FeeCalculator::new(&config, 0).unwrap();
+    let stats = CellTreeStats {
+        bit_count: 10,
+        cell_count: 1,
+    };
+    // 50 seconds before the first period are free
+    assert_eq!(
+        fees.compute_storage_fee(stats, 50, 150),
+        Tokens::new(12 * 50)
+    );
+    // Both periods
+    assert_eq!(
+        fees.compute_storage_fee(stats, 150, 250),
+        Tokens::new(12 * 50 + 34 * 50)
+    );
+    // Only the last period
+    assert_eq!(
+        fees.compute_storage_fee(stats, 200, 300),
+        Tokens::new(34 * 100)
+    );
+}
+
+#[test]
+fn storage_fee_between() {
+    use crate::models::StorageUsed;
+    use crate::num::VarUint56;
+
+    let prices = [(100, 1, 2), (200, 3, 4), (300, 5 << 16, 6 << 16)].map(|(since, bit, cell)| {
+        StoragePrices {
+            utime_since: since,
+            bit_price_ps: bit,
+            cell_price_ps: cell,
+            mc_bit_price_ps: bit * 1000,
+            mc_cell_price_ps: cell * 1000,
+        }
+    });
+    let mut dict = Dict::new();
+    for (i, item) in prices.iter().enumerate() {
+        dict.set(i as u32, item).unwrap();
+    }
+
+    let used = StorageUsed {
+        cells: VarUint56::new(1),
+        bits: VarUint56::new(1),
+        public_cells: VarUint56::ZERO,
+    };
+
+    // Rounding is applied only once for the whole interval
+    let fee = compute_storage_fee_between(&dict, &used, false, 150, 250).unwrap();
+    assert_eq!(fee, Tokens::new(1));
+    assert_eq!(
+        prices[0].compute_storage_fee(
+            false,
+            50,
+            CellTreeStats {
+                bit_count: 1,
+                cell_count: 1,
+            }
+        ),
+        Tokens::new(1)
+    );
+
+    // Exact values for the whole periods
+    let fee = compute_storage_fee_between(&dict, &used, false, 300, 310).unwrap();
+    assert_eq!(fee, Tokens::new(11 * 10));
+    let fee = compute_storage_fee_between(&dict, &used, false, 50, 310).unwrap();
+    assert_eq!(fee, Tokens::new(11 * 10 + 1));
+
+    let raw: u128 = (3 * 100 + 7 * 100) * 1000 + 11 * 1000 * 10 * 65536;
+    let fee = compute_storage_fee_between(&dict, &used, true, 100, 310).unwrap();
+    assert_eq!(fee, Tokens::new(raw.div_ceil(65536)));
+
+    // Empty intervals
+    let fee = compute_storage_fee_between(&dict, &used, false, 50, 100).unwrap();
+    assert_eq!(fee, Tokens::ZERO);
+    let fee = compute_storage_fee_between(&dict, &used, false, 400, 300).unwrap();
+    assert_eq!(fee, Tokens::ZERO);
+    // Storage was never paid
+    let fee = compute_storage_fee_between(&dict, &used, false, 0, 310).unwrap();
+    assert_eq!(fee, Tokens::ZERO);
+    let fee = compute_storage_fee_between(&Dict::new(), &used, false, 0, 300).unwrap();
+    assert_eq!(fee, Tokens::ZERO);
+}
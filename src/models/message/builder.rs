@@ -0,0 +1,226 @@
+use crate::cell::*;
+use crate::error::Error;
+use crate::num::Tokens;
+
+use crate::models::account::StateInit;
+use crate::models::currency::CurrencyCollection;
+
+use super::{
+    ExtAddr, ExtInMsgInfo, ExtOutMsgInfo, IntAddr, IntMsgInfo, MessageLayout, MsgInfo, OwnedMessage,
+};
+
+/// Message builder with an automatic layout selection.
+///
+/// # Example
+///
+/// ```
+/// # use everscale_types::prelude::*;
+/// # use everscale_types::models::{CurrencyCollection, IntAddr, MessageBuilder};
+/// # fn main() -> anyhow::Result<()> {
+/// let dst: IntAddr = "0:3333333333333333333333333333333333333333333333333333333333333333"
+///     .parse()?;
+///
+/// let mut body = CellBuilder::new();
+/// body.store_u32(0xdeafbeaf)?;
+///
+/// let value = CurrencyCollection::new(1000);
+/// let (cell, hash) = MessageBuilder::internal(IntAddr::default(), dst, value)
+///     .with_bounce(false)
+///     .with_body_builder(body)
+///     .build()?;
+/// assert_eq!(cell.repr_hash(), &hash);
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageBuilder {
+    info: MsgInfo,
+    init: Option<StateInit>,
+    body: MessageBody,
+}
+
+#[derive(Debug, Clone)]
+enum MessageBody {
+    Cell(Cell),
+    Builder(CellBuilder),
+}
+
+impl MessageBuilder {
+    /// Creates a builder for a message with the specified info.
+    pub fn new<T: Into<MsgInfo>>(info: T) -> Self {
+        Self {
+            info: info.into(),
+            init: None,
+            body: MessageBody::Builder(CellBuilder::new()),
+        }
+    }
+
+    /// Creates a builder for a bounceable internal message
+    /// with disabled IHR and zero fees.
+    pub fn internal(src: IntAddr, dst: IntAddr, value: CurrencyCollection) -> Self {
+        Self::new(IntMsgInfo {
+            ihr_disabled: true,
+            bounce: true,
+            src,
+            dst,
+            value,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a builder for an external incoming message without source
+    /// and with zero import fee.
+    pub fn external_in(dst: IntAddr) -> Self {
+        Self::new(ExtInMsgInfo {
+            src: None,
+            dst,
+            import_fee: Tokens::ZERO,
+        })
+    }
+
+    /// Creates a builder for an external outgoing message without destination.
+    pub fn external_out(src: IntAddr) -> Self {
+        Self::new(ExtOutMsgInfo {
+            src,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the message info.
+    #[inline]
+    pub fn info(&self) -> &MsgInfo {
+        &self.info
+    }
+
+    /// Returns a mutable reference to the message info.
+    #[inline]
+    pub fn info_mut(&mut self) -> &mut MsgInfo {
+        &mut self.info
+    }
+
+    /// Sets whether to bounce the message back if the destination transaction fails.
+    ///
+    /// NOTE: has no effect on external messages.
+    pub fn with_bounce(mut self, bounce: bool) -> Self {
+        if let MsgInfo::Int(info) = &mut self.info {
+            info.bounce = bounce;
+        }
+        self
+    }
+
+    /// Sets whether IHR is disabled for the message.
+    ///
+    /// NOTE: has no effect on external messages.
+    pub fn with_ihr_disabled(mut self, ihr_disabled: bool) -> Self {
+        if let MsgInfo::Int(info) = &mut self.info {
+            info.ihr_disabled = ihr_disabled;
+        }
+        self
+    }
+
+    /// Sets the external address of the message.
+    ///
+    /// Source for the external incoming messages and destination
+    /// for the external outgoing ones.
+    ///
+    /// NOTE: has no effect on internal messages.
+    pub fn with_ext_addr(mut self, addr: Option<ExtAddr>) -> Self {
+        match &mut self.info {
+            MsgInfo::Int(_) => {}
+            MsgInfo::ExtIn(info) => info.src = addr,
+            MsgInfo::ExtOut(info) => info.dst = addr,
+        }
+        self
+    }
+
+    /// Sets the logical time when the message was created.
+    ///
+    /// NOTE: has no effect on external incoming messages.
+    pub fn with_created_lt(mut self, created_lt: u64) -> Self {
+        match &mut self.info {
+            MsgInfo::Int(info) => info.created_lt = created_lt,
+            MsgInfo::ExtIn(_) => {}
+            MsgInfo::ExtOut(info) => info.created_lt = created_lt,
+        }
+        self
+    }
+
+    /// Sets the unix timestamp when the message was created.
+    ///
+    /// NOTE: has no effect on external incoming messages.
+    pub fn with_created_at(mut self, created_at: u32) -> Self {
+        match &mut self.info {
+            MsgInfo::Int(info) => info.created_at = created_at,
+            MsgInfo::ExtIn(_) => {}
+            MsgInfo::ExtOut(info) => info.created_at = created_at,
+        }
+        self
+    }
+
+    /// Sets an optional state init.
+    pub fn with_state_init<T: Into<Option<StateInit>>>(mut self, init: T) -> Self {
+        self.init = init.into();
+        self
+    }
+
+    /// Sets the message body from a cell.
+    pub fn with_body(mut self, body: Cell) -> Self {
+        self.body = MessageBody::Cell(body);
+        self
+    }
+
+    /// Sets the message body from an unfinished cell.
+    pub fn with_body_builder(mut self, body: CellBuilder) -> Self {
+        self.body = MessageBody::Builder(body);
+        self
+    }
+
+    /// Builds a message cell using an empty cell context.
+    /// Returns the cell and its representation hash.
+    pub fn build(self) -> Result<(Cell, HashBytes), Error> {
+        self.build_ext(Cell::empty_context())
+    }
+
+    /// Builds a message cell using the specified cell context.
+    /// Returns the cell and its representation hash.
+    pub fn build_ext(self, context: &dyn CellContext) -> Result<(Cell, HashBytes), Error> {
+        let message = ok!(self.build_message_ext(context));
+
+        let mut builder = CellBuilder::new();
+        ok!(message.store_into(&mut builder, context));
+        let cell = ok!(builder.build_ext(context));
+
+        let hash = *cell.repr_hash();
+        Ok((cell, hash))
+    }
+
+    /// Builds an owned message with the computed layout.
+    pub fn build_message(self) -> Result<OwnedMessage, Error> {
+        self.build_message_ext(Cell::empty_context())
+    }
+
+    /// Builds an owned message with the computed layout
+    /// using the specified cell context.
+    pub fn build_message_ext(self, context: &dyn CellContext) -> Result<OwnedMessage, Error> {
+        let body = match self.body {
+            MessageBody::Cell(cell) => cell,
+            MessageBody::Builder(builder) => ok!(builder.build_ext(context)),
+        };
+        let body_range = CellSliceRange::full(body.as_ref());
+
+        let (layout, size) = MessageLayout::compute(
+            self.info.exact_size(),
+            self.init.as_ref(),
+            body_range.size(),
+        );
+        if size.bits > MAX_BIT_LEN || size.refs > MAX_REF_COUNT as u8 {
+            return Err(Error::CellOverflow);
+        }
+
+        Ok(OwnedMessage {
+            info: self.info,
+            init: self.init,
+            body: (body, body_range),
+            layout: Some(layout),
+        })
+    }
+}
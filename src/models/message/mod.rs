@@ -10,11 +10,13 @@ use crate::models::account::StateInit;
 use crate::models::currency::CurrencyCollection;
 
 pub use self::address::*;
+pub use self::builder::*;
 pub use self::envelope::*;
 pub use self::in_message::*;
 pub use self::out_message::*;
 
 mod address;
+mod builder;
 mod envelope;
 mod in_message;
 mod out_message;
@@ -213,3 +213,119 @@ fn internal_message_with_deploy_special() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn message_builder() -> anyhow::Result<()> {
+    use crate::models::account::*;
+
+    // Internal message with a body which fits into the root cell
+    let src: IntAddr =
+        "0:82615d4ce6bcd9989a82c9329f65569922f3437830eaa1003444b3fa4a46490f".parse()?;
+    let dst: IntAddr =
+        "0:a732bba1c348ddae0970a541276e9cde4e44ac2c55e8079d034f88b0304f7c08".parse()?;
+
+    let mut body = CellBuilder::new();
+    body.store_u32(0xdeafbeaf)?;
+
+    let (cell, hash) =
+        MessageBuilder::internal(src.clone(), dst.clone(), CurrencyCollection::new(1000))
+            .with_created_lt(123)
+            .with_created_at(456)
+            .with_body_builder(body)
+            .build()?;
+    assert_eq!(cell.repr_hash(), &hash);
+
+    let mut message = cell.parse::<Message>()?;
+    assert_eq!(message.layout, Some(MessageLayout::plain()));
+    assert_eq!(message.body.load_u32()?, 0xdeafbeaf);
+    let MsgInfo::Int(info) = message.info else {
+        panic!("expected an internal message");
+    };
+    assert_eq!(
+        info,
+        IntMsgInfo {
+            ihr_disabled: true,
+            bounce: true,
+            bounced: false,
+            src: src.clone(),
+            dst: dst.clone(),
+            value: CurrencyCollection::new(1000),
+            ihr_fee: Tokens::ZERO,
+            fwd_fee: Tokens::ZERO,
+            created_lt: 123,
+            created_at: 456,
+        }
+    );
+
+    // Same as manually assembled message
+    let body = Boc::decode(include_bytes!("internal_message_body.boc"))?;
+    let (cell, _) = MessageBuilder::internal(src.clone(), dst.clone(), CurrencyCollection::new(1))
+        .with_body(body.clone())
+        .build()?;
+
+    let expected = serialize_message(Message {
+        info: MsgInfo::Int(IntMsgInfo {
+            bounce: true,
+            src,
+            dst,
+            value: CurrencyCollection::new(1),
+            ..Default::default()
+        }),
+        init: None,
+        body: body.as_slice()?,
+        layout: None,
+    });
+    assert_eq!(cell, expected);
+
+    // Deploy with a body which doesn't fit into the root cell
+    let init = Boc::decode(include_bytes!(
+        "internal_message_with_deploy_state_init.boc"
+    ))?
+    .parse::<StateInit>()?;
+    let body = Boc::decode(include_bytes!("internal_message_with_deploy_body.boc"))?;
+
+    let dst: IntAddr =
+        "0:a4232bb25ca73b09e1bb5200f87548f5a51a2d143d296a5a86b4bf74ec83e662".parse()?;
+    let message = MessageBuilder::external_in(dst.clone())
+        .with_state_init(init.clone())
+        .with_body(body.clone())
+        .build_message()?;
+    assert_eq!(
+        message.layout,
+        Some(OwnedMessage::compute_layout(
+            &message.info,
+            Some(&init),
+            &message.body
+        ))
+    );
+
+    let cell = CellBuilder::build_from(&message)?;
+    let parsed = cell.parse::<Message>()?;
+    assert_eq!(parsed.ty(), MsgType::ExtIn);
+    assert_eq!(parsed.init.as_ref(), Some(&init));
+    assert_eq!(parsed.layout, message.layout);
+    assert_eq!(
+        CellBuilder::build_from(parsed.body)?.repr_hash(),
+        body.repr_hash()
+    );
+
+    // External outgoing message
+    let (cell, _) = MessageBuilder::external_out(dst.clone())
+        .with_bounce(true)
+        .with_created_lt(41854595000003)
+        .with_created_at(1694436128)
+        .build()?;
+    let parsed = cell.parse::<Message>()?;
+    assert_eq!(
+        parsed.info,
+        MsgInfo::ExtOut(ExtOutMsgInfo {
+            src: dst,
+            dst: None,
+            created_lt: 41854595000003,
+            created_at: 1694436128,
+        })
+    );
+    assert!(parsed.body.is_data_empty() && parsed.body.is_refs_empty());
+
+    Ok(())
+}
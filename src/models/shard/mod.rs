@@ -6,7 +6,9 @@ use std::sync::OnceLock;
 use crate::cell::*;
 use crate::dict::Dict;
 use crate::error::*;
+use crate::merkle::MerkleProof;
 
+use crate::models::account::ShardAccount;
 use crate::models::block::{BlockRef, ShardIdent};
 use crate::models::currency::CurrencyCollection;
 use crate::models::Lazy;
@@ -14,6 +16,7 @@ use crate::models::Lazy;
 #[cfg(feature = "tycho")]
 use crate::models::ShardIdentFull;
 
+pub use self::out_msg_queue::*;
 pub use self::shard_accounts::*;
 pub use self::shard_extra::*;
 
@@ -22,6 +25,7 @@ use super::MsgsExecutionParams;
 #[cfg(feature = "venom")]
 use super::ShardBlockRefs;
 
+mod out_msg_queue;
 mod shard_accounts;
 mod shard_extra;
 
@@ -138,9 +142,9 @@ pub struct ShardStateUnsplit {
     /// Minimal referenced seqno of the masterchain block.
     pub min_ref_mc_seqno: u32,
 
-    /// Output messages queue info (stub).
+    /// Output messages queue info.
     #[cfg(not(feature = "tycho"))]
-    pub out_msg_queue_info: Cell,
+    pub out_msg_queue_info: Lazy<OutMsgQueueInfo>,
 
     /// Processed up to info for externals and internals.
     #[cfg(feature = "tycho")]
@@ -183,7 +187,7 @@ impl Default for ShardStateUnsplit {
             gen_lt: 0,
             min_ref_mc_seqno: 0,
             #[cfg(not(feature = "tycho"))]
-            out_msg_queue_info: Cell::default(),
+            out_msg_queue_info: Self::empty_out_msg_queue_info().clone(),
             #[cfg(feature = "tycho")]
             processed_upto: Self::empty_processed_upto_info().clone(),
             before_split: false,
@@ -213,6 +217,13 @@ impl ShardStateUnsplit {
         PROCESSED_UPTO_INFO.get_or_init(|| Lazy::new(&ProcessedUptoInfo::default()).unwrap())
     }
 
+    /// Returns a static reference to the empty output messages queue info.
+    #[cfg(all(feature = "sync", not(feature = "tycho")))]
+    pub fn empty_out_msg_queue_info() -> &'static Lazy<OutMsgQueueInfo> {
+        static OUT_MSG_QUEUE_INFO: OnceLock<Lazy<OutMsgQueueInfo>> = OnceLock::new();
+        OUT_MSG_QUEUE_INFO.get_or_init(|| Lazy::new(&OutMsgQueueInfo::default()).unwrap())
+    }
+
     /// Returns a static reference to the empty shard accounts.
     #[cfg(feature = "sync")]
     pub fn empty_shard_accounts() -> &'static Lazy<ShardAccounts> {
@@ -225,6 +236,12 @@ impl ShardStateUnsplit {
         self.accounts.load()
     }
 
+    /// Tries to load output messages queue info.
+    #[cfg(not(feature = "tycho"))]
+    pub fn load_out_msg_queue_info(&self) -> Result<OutMsgQueueInfo, Error> {
+        self.out_msg_queue_info.load()
+    }
+
     /// Tries to load additional masterchain data.
     pub fn load_custom(&self) -> Result<Option<McStateExtra>, Error> {
         match &self.custom {
@@ -251,6 +268,204 @@ impl ShardStateUnsplit {
             (Some(custom), Some(value)) => custom.set(value),
         }
     }
+
+    /// Creates a Merkle proof for the account state in the shard state
+    /// with the specified root.
+    ///
+    /// The proof contains only the path through shard accounts to the
+    /// account and the account cell itself (its code and data are pruned).
+    /// A proof for a non-existing account shows its absence.
+    pub fn prove_account(state_root: &Cell, account_id: &HashBytes) -> Result<MerkleProof, Error> {
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let state = ok!(usage_tree.track(state_root).parse::<Self>());
+        let accounts = ok!(state.load_accounts());
+        if let Some((_, account)) = ok!(accounts.get(account_id)) {
+            ok!(account.load_account());
+        }
+
+        MerkleProof::create(state_root.as_ref(), usage_tree).build()
+    }
+
+    /// Checks the account state proof against the shard state hash
+    /// (e.g. the new hash of the block state update).
+    ///
+    /// Returns the account state or `None` if the account doesn't exist.
+    pub fn verify_account_proof(
+        proof: &MerkleProof,
+        state_hash: &HashBytes,
+        account_id: &HashBytes,
+    ) -> Result<Option<ShardAccount>, Error> {
+        let state_root = ok!(proof.verify(state_hash));
+        let state = ok!(state_root.parse::<Self>());
+        let accounts = ok!(state.load_accounts());
+        match ok!(accounts.get(account_id)) {
+            Some((_, account)) => Ok(Some(account)),
+            None => Ok(None),
+        }
+    }
+
+    /// Splits the shard state into the states of two child shards.
+    ///
+    /// Accounts are partitioned by the child shard prefix. Total balances
+    /// are recomputed for each child. Pending validator fees are kept
+    /// in the left state. Message queue info is partitioned between the children,
+    /// see [`OutMsgQueueInfo::filter_by_shard`].
+    ///
+    /// Fails with [`Error::InvalidData`] for masterchain states
+    /// or shards that can't be split.
+    pub fn split(&self) -> Result<(Self, Self), Error> {
+        if self.shard_ident.is_masterchain() || self.custom.is_some() {
+            return Err(Error::InvalidData);
+        }
+        let Some((left_shard, right_shard)) = self.shard_ident.split() else {
+            return Err(Error::InvalidData);
+        };
+
+        let accounts = ok!(self.load_accounts());
+        let (left_accounts, right_accounts) = {
+            let prefix_len = self.shard_ident.prefix_len();
+            let mut prefix = CellBuilder::new();
+            if prefix_len > 0 {
+                ok!(prefix.store_uint(self.shard_ident.prefix() >> (64 - prefix_len), prefix_len));
+            }
+            ok!(accounts.split_by_prefix(&prefix.as_data_slice()))
+        };
+
+        #[cfg(not(feature = "tycho"))]
+        let out_msg_queue_info = ok!(self.load_out_msg_queue_info());
+
+        let make_child = |shard_ident: ShardIdent,
+                          accounts: &ShardAccounts,
+                          total_validator_fees: CurrencyCollection| {
+            Ok::<_, Error>(Self {
+                shard_ident,
+                #[cfg(not(feature = "tycho"))]
+                out_msg_queue_info: ok!(Lazy::new(&ok!(
+                    out_msg_queue_info.filter_by_shard(&shard_ident)
+                ))),
+                before_split: false,
+                accounts: ok!(Lazy::new(accounts)),
+                overload_history: 0,
+                underload_history: 0,
+                total_balance: accounts.root_extra().balance.clone(),
+                total_validator_fees,
+                #[cfg(feature = "venom")]
+                shard_block_refs: None,
+                ..self.clone()
+            })
+        };
+
+        let left = ok!(make_child(
+            left_shard,
+            &left_accounts,
+            self.total_validator_fees.clone(),
+        ));
+        let right = ok!(make_child(
+            right_shard,
+            &right_accounts,
+            CurrencyCollection::ZERO,
+        ));
+        Ok((left, right))
+    }
+
+    /// Merges the states of two sibling shards into the state of their parent.
+    ///
+    /// The resulting state has the latest generation time and logical time
+    /// of both states, the merged accounts with a recomputed total balance
+    /// and the sum of pending validator fees. Message queue info is combined
+    /// from both states (processed upto info is taken from the left state).
+    ///
+    /// Fails with [`Error::InvalidData`] if the states are not siblings,
+    /// belong to different networks, or contain accounts from other shards.
+    pub fn merge(left: &Self, right: &Self) -> Result<Self, Error> {
+        let Some(shard_ident) = left.shard_ident.merge() else {
+            return Err(Error::InvalidData);
+        };
+        if !left.shard_ident.is_left_child()
+            || left.shard_ident.opposite() != Some(right.shard_ident)
+            || left.shard_ident.is_masterchain()
+            || left.global_id != right.global_id
+            || left.custom.is_some()
+            || right.custom.is_some()
+            || left.libraries != right.libraries
+        {
+            return Err(Error::InvalidData);
+        }
+
+        let mut accounts = ok!(left.load_accounts());
+        for key in accounts.keys() {
+            if !left.shard_ident.contains_account(&ok!(key)) {
+                return Err(Error::InvalidData);
+            }
+        }
+        for entry in ok!(right.load_accounts()).iter() {
+            let (key, extra, account) = ok!(entry);
+            if !right.shard_ident.contains_account(&key) {
+                return Err(Error::InvalidData);
+            }
+            ok!(accounts.set(key, extra, account));
+        }
+
+        #[cfg(not(feature = "tycho"))]
+        let out_msg_queue_info = {
+            let mut info = ok!(left.load_out_msg_queue_info());
+            let right = ok!(right.load_out_msg_queue_info());
+            for entry in right.out_queue.iter() {
+                let (key, lt, msg) = ok!(entry);
+                ok!(info.out_queue.set(key, lt, msg));
+            }
+            for entry in right.proc_info.iter() {
+                let (key, value) = ok!(entry);
+                ok!(info.proc_info.set(key, value));
+            }
+            for entry in right.ihr_pending.iter() {
+                let (key, value) = ok!(entry);
+                ok!(info.ihr_pending.set(key, value));
+            }
+            ok!(Lazy::new(&info))
+        };
+
+        let latest = if (right.gen_utime, right.gen_lt) > (left.gen_utime, left.gen_lt) {
+            right
+        } else {
+            left
+        };
+
+        let master_ref = match (&left.master_ref, &right.master_ref) {
+            (Some(l), Some(r)) if r.seqno > l.seqno => Some(r.clone()),
+            (Some(l), _) => Some(l.clone()),
+            (None, r) => r.clone(),
+        };
+
+        Ok(Self {
+            global_id: left.global_id,
+            shard_ident,
+            seqno: std::cmp::max(left.seqno, right.seqno),
+            vert_seqno: std::cmp::max(left.vert_seqno, right.vert_seqno),
+            gen_utime: latest.gen_utime,
+            #[cfg(any(feature = "venom", feature = "tycho"))]
+            gen_utime_ms: latest.gen_utime_ms,
+            gen_lt: latest.gen_lt,
+            min_ref_mc_seqno: std::cmp::min(left.min_ref_mc_seqno, right.min_ref_mc_seqno),
+            #[cfg(not(feature = "tycho"))]
+            out_msg_queue_info,
+            #[cfg(feature = "tycho")]
+            processed_upto: left.processed_upto.clone(),
+            before_split: false,
+            total_balance: accounts.root_extra().balance.clone(),
+            accounts: ok!(Lazy::new(&accounts)),
+            overload_history: 0,
+            underload_history: 0,
+            total_validator_fees: ok!(left
+                .total_validator_fees
+                .checked_add(&right.total_validator_fees)),
+            libraries: left.libraries.clone(),
+            master_ref,
+            custom: None,
+            #[cfg(feature = "venom")]
+            shard_block_refs: None,
+        })
+    }
 }
 
 impl Store for ShardStateUnsplit {
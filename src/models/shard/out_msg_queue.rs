@@ -0,0 +1,272 @@
+use crate::cell::*;
+use crate::dict::{AugDict, AugDictExtra, Dict, DictKey};
+use crate::error::Error;
+
+use crate::models::block::ShardIdent;
+use crate::models::message::{EnqueuedMsg, IntermediateAddr, MsgEnvelope};
+
+/// Outbound message queue info.
+///
+/// # TLB scheme
+///
+/// ```text
+/// _ out_queue:OutMsgQueue proc_info:ProcessedInfo
+///     ihr_pending:IhrPendingInfo = OutMsgQueueInfo;
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
+pub struct OutMsgQueueInfo {
+    /// Enqueued outbound messages.
+    pub out_queue: OutMsgQueue,
+    /// Processed messages info.
+    pub proc_info: ProcessedInfo,
+    /// Pending IHR messages info.
+    pub ihr_pending: IhrPendingInfo,
+}
+
+impl OutMsgQueueInfo {
+    /// Finds an enqueued message for the specified destination by its logical time.
+    pub fn find_message(
+        &self,
+        workchain: i32,
+        prefix: u64,
+        lt: u64,
+    ) -> Result<Option<(OutMsgQueueKey, EnqueuedMsg)>, Error> {
+        for entry in self.iter_messages(workchain, prefix) {
+            let (key, msg) = ok!(entry);
+            if msg.enqueued_lt == lt {
+                return Ok(Some((key, msg)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Gets an iterator over the enqueued messages for the specified destination,
+    /// sorted by message hash.
+    pub fn iter_messages(&self, workchain: i32, prefix: u64) -> OutMsgQueueIter<'_> {
+        OutMsgQueueIter {
+            queue: &self.out_queue,
+            key: Some(OutMsgQueueKey {
+                workchain,
+                prefix,
+                hash: HashBytes::ZERO,
+            }),
+            inclusive: true,
+        }
+    }
+
+    /// Returns the processed message info for the specified shard and masterchain seqno.
+    pub fn get_processed_upto(
+        &self,
+        shard: u64,
+        mc_seqno: u32,
+    ) -> Result<Option<ProcessedUpto>, Error> {
+        self.proc_info.get(ProcessedUptoKey { shard, mc_seqno })
+    }
+
+    /// Returns the part of the queue info which belongs to the specified shard.
+    ///
+    /// - Enqueued messages are kept if their current address is in the shard;
+    /// - Processed messages info is kept if its shard intersects with the specified one;
+    /// - Pending IHR messages are kept if their destination is in the shard.
+    pub fn filter_by_shard(&self, shard: &ShardIdent) -> Result<Self, Error> {
+        let workchain = shard.workchain();
+
+        let mut out_queue = OutMsgQueue::new();
+        for entry in self.out_queue.iter() {
+            let (key, extra, msg) = ok!(entry);
+            let envelope = ok!(msg.out_msg_envelope.load());
+            if shard.contains_prefix(workchain, ok!(envelope_cur_prefix(&envelope))) {
+                ok!(out_queue.set(key, extra, msg));
+            }
+        }
+
+        let mut proc_info = ProcessedInfo::new();
+        for entry in self.proc_info.iter() {
+            let (key, value) = ok!(entry);
+            let Some(entry_shard) = ShardIdent::new(workchain, key.shard) else {
+                return Err(Error::InvalidData);
+            };
+            if entry_shard.intersects(shard) {
+                ok!(proc_info.set(key, value));
+            }
+        }
+
+        let mut ihr_pending = IhrPendingInfo::new();
+        for entry in self.ihr_pending.iter() {
+            let (key, value) = ok!(entry);
+            if shard.contains_prefix(workchain, key.prefix) {
+                ok!(ihr_pending.set(key, value));
+            }
+        }
+
+        Ok(Self {
+            out_queue,
+            proc_info,
+            ihr_pending,
+        })
+    }
+}
+
+/// Computes the address prefix of the current message location.
+fn envelope_cur_prefix(envelope: &MsgEnvelope) -> Result<u64, Error> {
+    match &envelope.cur_addr {
+        IntermediateAddr::Regular(addr) => {
+            let info = ok!(envelope.load_message_info());
+
+            // NOTE: the first 32 bits of the intermediate address are the workchain
+            let dest_bits = addr.use_dest_bits().saturating_sub(32) as u32;
+            let dest_mask = match dest_bits {
+                0 => 0,
+                bits => u64::MAX << (64 - bits),
+            };
+            Ok((info.dst.prefix() & dest_mask) | (info.src.prefix() & !dest_mask))
+        }
+        IntermediateAddr::Simple(addr) => Ok(addr.address_prefix),
+        IntermediateAddr::Ext(addr) => Ok(addr.address_prefix),
+    }
+}
+
+/// An iterator over the enqueued messages with the same destination.
+///
+/// See [`OutMsgQueueInfo::iter_messages`] for more details.
+#[derive(Clone)]
+pub struct OutMsgQueueIter<'a> {
+    queue: &'a OutMsgQueue,
+    key: Option<OutMsgQueueKey>,
+    inclusive: bool,
+}
+
+impl Iterator for OutMsgQueueIter<'_> {
+    type Item = Result<(OutMsgQueueKey, EnqueuedMsg), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.key.take()?;
+
+        let dict = self.queue.dict();
+        let next = if std::mem::take(&mut self.inclusive) {
+            dict.get_or_next(key, false)
+        } else {
+            dict.get_next(key, false)
+        };
+
+        match next {
+            Ok(Some((next_key, (_, msg))))
+                if next_key.workchain == key.workchain && next_key.prefix == key.prefix =>
+            {
+                self.key = Some(next_key);
+                Some(Ok((next_key, msg)))
+            }
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// A dictionary of enqueued outbound messages,
+/// augmented with the minimal enqueued logical time.
+///
+/// # TLB scheme
+///
+/// ```text
+/// _ enqueued_lt:uint64 out_msg:^MsgEnvelope = EnqueuedMsg;
+/// _ (HashmapAugE 352 EnqueuedMsg uint64) = OutMsgQueue;
+/// ```
+pub type OutMsgQueue = AugDict<OutMsgQueueKey, EnqueuedMsgMinLt, EnqueuedMsg>;
+
+/// Value augmentation for the [`OutMsgQueue`] dictionary.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Store, Load)]
+pub struct EnqueuedMsgMinLt {
+    /// The minimal enqueued logical time in a subtree.
+    pub min_lt: u64,
+}
+
+impl AugDictExtra for EnqueuedMsgMinLt {
+    fn comp_add(
+        left: &mut CellSlice,
+        right: &mut CellSlice,
+        b: &mut CellBuilder,
+        cx: &dyn CellContext,
+    ) -> Result<(), Error> {
+        let left = ok!(Self::load_from(left));
+        let right = ok!(Self::load_from(right));
+        Self {
+            min_lt: std::cmp::min(left.min_lt, right.min_lt),
+        }
+        .store_into(b, cx)
+    }
+}
+
+/// Key of the [`OutMsgQueue`] dictionary.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Store, DictKey)]
+pub struct OutMsgQueueKey {
+    /// Next-hop workchain id.
+    pub workchain: i32,
+    /// Next-hop address prefix.
+    pub prefix: u64,
+    /// Message hash.
+    pub hash: HashBytes,
+}
+
+/// A dictionary of processed messages info.
+///
+/// # TLB scheme
+///
+/// ```text
+/// _ (HashmapE 96 ProcessedUpto) = ProcessedInfo;
+/// ```
+pub type ProcessedInfo = Dict<ProcessedUptoKey, ProcessedUpto>;
+
+/// Key of the [`ProcessedInfo`] dictionary.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Store, DictKey)]
+pub struct ProcessedUptoKey {
+    /// Shard prefix with termination bit.
+    pub shard: u64,
+    /// Masterchain block seqno.
+    pub mc_seqno: u32,
+}
+
+/// The last processed message.
+///
+/// # TLB scheme
+///
+/// ```text
+/// processed_upto$_ last_msg_lt:uint64 last_msg_hash:bits256 = ProcessedUpto;
+/// ```
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Store, Load)]
+pub struct ProcessedUpto {
+    /// Logical time of the last processed message.
+    pub last_msg_lt: u64,
+    /// Hash of the last processed message.
+    pub last_msg_hash: HashBytes,
+}
+
+/// A dictionary of pending IHR messages.
+///
+/// # TLB scheme
+///
+/// ```text
+/// _ (HashmapE 320 IhrPendingSince) = IhrPendingInfo;
+/// ```
+pub type IhrPendingInfo = Dict<IhrPendingKey, IhrPendingSince>;
+
+/// Key of the [`IhrPendingInfo`] dictionary.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Store, DictKey)]
+pub struct IhrPendingKey {
+    /// Destination address prefix.
+    pub prefix: u64,
+    /// Message hash.
+    pub hash: HashBytes,
+}
+
+/// Pending IHR message info.
+///
+/// # TLB scheme
+///
+/// ```text
+/// ihr_pending$_ import_lt:uint64 = IhrPendingSince;
+/// ```
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Store, Load)]
+pub struct IhrPendingSince {
+    /// Logical time when the message was imported.
+    pub import_lt: u64,
+}
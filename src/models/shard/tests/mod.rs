@@ -7,6 +7,15 @@ fn check_master_state(cell: Cell) {
     println!("data: {data:#?}");
     assert_eq!(CellBuilder::build_from(&data).unwrap(), cell);
 
+    #[cfg(not(feature = "tycho"))]
+    {
+        let out_msg_queue_info = data.load_out_msg_queue_info().unwrap();
+        assert_eq!(
+            CellBuilder::build_from(&out_msg_queue_info).unwrap(),
+            data.out_msg_queue_info.cell
+        );
+    }
+
     let shard_accounts = data.load_accounts().unwrap();
     assert_eq!(
         CellBuilder::build_from(&shard_accounts).unwrap(),
@@ -58,3 +67,428 @@ fn new_zerostate() {
     let new_state = state_update.apply(&zerostate).unwrap();
     check_master_state(new_state);
 }
+
+#[test]
+fn zerostate_storage_fees() {
+    use crate::models::{FeeCalculator, TxInfo};
+
+    let zerostate = Boc::decode(include_bytes!("new_zerostate.boc")).unwrap();
+    let zerostate = zerostate.parse::<ShardStateUnsplit>().unwrap();
+    let config = zerostate.load_custom().unwrap().unwrap().config;
+    let fees = FeeCalculator::new(&config.params, -1).unwrap();
+    let accounts = zerostate.load_accounts().unwrap();
+
+    let block = Boc::decode(include_bytes!("first_block.boc")).unwrap();
+    let block = block.parse::<Block>().unwrap();
+
+    let mut checked = 0;
+    for entry in block.iter_transactions() {
+        let (account, _, tx, _) = entry.unwrap();
+        let storage_phase = match tx.load_info().unwrap() {
+            TxInfo::Ordinary(info) => info.storage_phase.unwrap(),
+            TxInfo::TickTock(info) => info.storage_phase,
+        };
+
+        let (_, shard_account) = accounts.get(account).unwrap().unwrap();
+        let storage_stat = shard_account.load_account().unwrap().unwrap().storage_stat;
+        let stats = CellTreeStats {
+            bit_count: storage_stat.used.bits.into_inner(),
+            cell_count: storage_stat.used.cells.into_inner(),
+        };
+
+        // Zerostate accounts have never paid for storage
+        assert_eq!(storage_stat.last_paid, 0);
+        assert_eq!(
+            fees.compute_storage_fee(stats, storage_stat.last_paid, tx.now),
+            storage_phase.storage_fees_collected
+        );
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn account_proof() {
+    const BOC: &[u8] = include_bytes!("everscale_zerostate.boc");
+    let state_root = Boc::decode(BOC).unwrap();
+    let state_hash = state_root.repr_hash();
+
+    let elector_id = HashBytes([0x33; 32]);
+    let proof = ShardStateUnsplit::prove_account(&state_root, &elector_id).unwrap();
+    assert!(Boc::encode(&proof.cell).len() < BOC.len());
+
+    let shard_account = ShardStateUnsplit::verify_account_proof(&proof, state_hash, &elector_id)
+        .unwrap()
+        .unwrap();
+    let state = state_root.parse::<ShardStateUnsplit>().unwrap();
+    let (_, expected) = state
+        .load_accounts()
+        .unwrap()
+        .get(elector_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(shard_account, expected);
+    assert_eq!(
+        shard_account.load_account().unwrap(),
+        expected.load_account().unwrap()
+    );
+
+    // Other accounts are pruned
+    let other_id = HashBytes([0x55; 32]);
+    assert_eq!(
+        ShardStateUnsplit::verify_account_proof(&proof, state_hash, &other_id).unwrap_err(),
+        Error::PrunedBranchAccess
+    );
+
+    // Proof of absence
+    let missing_id = HashBytes([0x42; 32]);
+    let proof = ShardStateUnsplit::prove_account(&state_root, &missing_id).unwrap();
+    assert_eq!(
+        ShardStateUnsplit::verify_account_proof(&proof, state_hash, &missing_id).unwrap(),
+        None
+    );
+
+    // Proof for a different state
+    assert_eq!(
+        ShardStateUnsplit::verify_account_proof(&proof, &HashBytes::ZERO, &missing_id).unwrap_err(),
+        Error::InvalidData
+    );
+}
+
+#[test]
+#[cfg(not(feature = "tycho"))]
+fn out_msg_queue_info() {
+    use crate::models::EnqueuedMsg;
+
+    let make_msg = |enqueued_lt: u64| EnqueuedMsg {
+        enqueued_lt,
+        out_msg_envelope: Lazy::from_raw(CellBuilder::build_from(enqueued_lt).unwrap()),
+    };
+
+    let keys = [
+        (0, 0x8000000000000000, [0x11; 32], 100),
+        (0, 0x8000000000000000, [0x22; 32], 300),
+        (0, 0x8000000000000000, [0x33; 32], 200),
+        (0, 0x4000000000000000, [0x11; 32], 50),
+        (-1, 0x8000000000000000, [0x44; 32], 150),
+    ];
+
+    let mut info = OutMsgQueueInfo::default();
+    for (workchain, prefix, hash, lt) in keys {
+        let key = OutMsgQueueKey {
+            workchain,
+            prefix,
+            hash: HashBytes(hash),
+        };
+        info.out_queue
+            .set(key, EnqueuedMsgMinLt { min_lt: lt }, make_msg(lt))
+            .unwrap();
+    }
+    assert_eq!(info.out_queue.root_extra().min_lt, 50);
+
+    info.proc_info
+        .set(
+            ProcessedUptoKey {
+                shard: 0x8000000000000000,
+                mc_seqno: 123,
+            },
+            ProcessedUpto {
+                last_msg_lt: 10,
+                last_msg_hash: HashBytes([0xaa; 32]),
+            },
+        )
+        .unwrap();
+    info.ihr_pending
+        .set(
+            IhrPendingKey {
+                prefix: 0x8000000000000000,
+                hash: HashBytes([0xbb; 32]),
+            },
+            IhrPendingSince { import_lt: 20 },
+        )
+        .unwrap();
+
+    // Check serialization
+    let cell = CellBuilder::build_from(&info).unwrap();
+    let parsed = cell.parse::<OutMsgQueueInfo>().unwrap();
+    assert_eq!(parsed, info);
+    assert_eq!(CellBuilder::build_from(&parsed).unwrap(), cell);
+
+    // Iterate messages for the destination
+    let lts = info
+        .iter_messages(0, 0x8000000000000000)
+        .map(|entry| entry.map(|(_, msg)| msg.enqueued_lt))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(lts, [100, 300, 200]);
+    assert_eq!(info.iter_messages(0, 0xc000000000000000).count(), 0);
+
+    // Find messages by lt
+    let (key, msg) = info
+        .find_message(0, 0x8000000000000000, 200)
+        .unwrap()
+        .unwrap();
+    assert_eq!(key.hash, HashBytes([0x33; 32]));
+    assert_eq!(msg, make_msg(200));
+    assert!(info
+        .find_message(-1, 0x8000000000000000, 150)
+        .unwrap()
+        .is_some());
+    assert!(info
+        .find_message(0, 0x8000000000000000, 150)
+        .unwrap()
+        .is_none());
+
+    // Processed info
+    let processed = info.get_processed_upto(0x8000000000000000, 123).unwrap();
+    assert_eq!(processed.unwrap().last_msg_lt, 10);
+    assert!(info
+        .get_processed_upto(0x8000000000000000, 124)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn split_merge_state() {
+    const BOC: &[u8] = include_bytes!("everscale_zerostate.boc");
+    let masterchain_state = Boc::decode(BOC)
+        .unwrap()
+        .parse::<ShardStateUnsplit>()
+        .unwrap();
+
+    // Masterchain state can't be split
+    assert_eq!(masterchain_state.split().unwrap_err(), Error::InvalidData);
+
+    let accounts = masterchain_state.load_accounts().unwrap();
+    let state = ShardStateUnsplit {
+        shard_ident: ShardIdent::new_full(0),
+        custom: None,
+        total_balance: accounts.root_extra().balance.clone(),
+        total_validator_fees: CurrencyCollection::new(1000),
+        ..masterchain_state.clone()
+    };
+
+    // Split
+    let (left, right) = state.split().unwrap();
+    assert_eq!(
+        left.shard_ident,
+        ShardIdent::new(0, 0x4000000000000000).unwrap()
+    );
+    assert_eq!(
+        right.shard_ident,
+        ShardIdent::new(0, 0xc000000000000000).unwrap()
+    );
+    assert_eq!(left.total_validator_fees, CurrencyCollection::new(1000));
+    assert_eq!(right.total_validator_fees, CurrencyCollection::ZERO);
+
+    let mut total_count = 0;
+    for child in [&left, &right] {
+        let child_accounts = child.load_accounts().unwrap();
+        assert_eq!(child.total_balance, child_accounts.root_extra().balance);
+
+        let mut balance = CurrencyCollection::ZERO;
+        for entry in child_accounts.iter() {
+            let (id, extra, _) = entry.unwrap();
+            assert!(child.shard_ident.contains_account(&id));
+            balance = balance.checked_add(&extra.balance).unwrap();
+            total_count += 1;
+        }
+        assert_eq!(balance, child.total_balance);
+    }
+    assert_eq!(total_count, accounts.keys().count());
+    assert_eq!(
+        left.total_balance
+            .checked_add(&right.total_balance)
+            .unwrap(),
+        state.total_balance
+    );
+
+    // Split the child state
+    let (left_left, left_right) = left.split().unwrap();
+    assert!(left_left.shard_ident.is_child_of(&left.shard_ident));
+    assert!(left_right.shard_ident.is_child_of(&left.shard_ident));
+
+    // Merge
+    let merged = ShardStateUnsplit::merge(&left, &right).unwrap();
+    assert_eq!(merged.shard_ident, state.shard_ident);
+    assert_eq!(merged.accounts.cell, state.accounts.cell);
+    assert_eq!(merged.total_balance, state.total_balance);
+    assert_eq!(merged.total_validator_fees, state.total_validator_fees);
+
+    let merged_left = ShardStateUnsplit::merge(&left_left, &left_right).unwrap();
+    assert_eq!(merged_left, left);
+
+    // Invalid merges
+    assert_eq!(
+        ShardStateUnsplit::merge(&right, &left).unwrap_err(),
+        Error::InvalidData
+    );
+    assert_eq!(
+        ShardStateUnsplit::merge(&left_left, &right).unwrap_err(),
+        Error::InvalidData
+    );
+
+    let foreign = ShardStateUnsplit {
+        global_id: left.global_id + 1,
+        ..left.clone()
+    };
+    assert_eq!(
+        ShardStateUnsplit::merge(&foreign, &right).unwrap_err(),
+        Error::InvalidData
+    );
+
+    let invalid = ShardStateUnsplit {
+        accounts: right.accounts.clone(),
+        ..left.clone()
+    };
+    assert_eq!(
+        ShardStateUnsplit::merge(&invalid, &right).unwrap_err(),
+        Error::InvalidData
+    );
+}
+
+#[test]
+#[cfg(not(feature = "tycho"))]
+fn split_out_msg_queue() {
+    use crate::models::{
+        EnqueuedMsg, IntAddr, IntermediateAddr, IntermediateAddrSimple, MessageBuilder,
+        MsgEnvelope, StdAddr,
+    };
+    use crate::num::Tokens;
+
+    const BOC: &[u8] = include_bytes!("everscale_zerostate.boc");
+    let masterchain_state = Boc::decode(BOC)
+        .unwrap()
+        .parse::<ShardStateUnsplit>()
+        .unwrap();
+
+    let make_addr = |byte: u8| IntAddr::Std(StdAddr::new(0, HashBytes([byte; 32])));
+
+    // (src, dst, cur_addr, lt)
+    let messages = [
+        (0x11, 0xee, IntermediateAddr::FULL_SRC_SAME_WORKCHAIN, 100),
+        (0x22, 0x33, IntermediateAddr::FULL_SRC_SAME_WORKCHAIN, 200),
+        (0x99, 0x11, IntermediateAddr::FULL_SRC_SAME_WORKCHAIN, 300),
+        (0xcc, 0xdd, IntermediateAddr::FULL_SRC_SAME_WORKCHAIN, 400),
+        // Transit messages with the current address in the other half
+        (0x11, 0xee, IntermediateAddr::FULL_DEST_SAME_WORKCHAIN, 500),
+        (
+            0xee,
+            0x11,
+            IntermediateAddr::Simple(IntermediateAddrSimple {
+                workchain: 0,
+                address_prefix: 0x3000000000000000,
+            }),
+            600,
+        ),
+    ];
+
+    let mut info = OutMsgQueueInfo::default();
+    for (src, dst, cur_addr, lt) in messages {
+        let dst = make_addr(dst);
+        let message = MessageBuilder::internal(make_addr(src), dst.clone(), Default::default())
+            .with_created_lt(lt)
+            .build_message()
+            .unwrap();
+        let envelope = MsgEnvelope {
+            cur_addr,
+            next_addr: IntermediateAddr::FULL_DEST_SAME_WORKCHAIN,
+            fwd_fee_remaining: Tokens::ZERO,
+            message: Lazy::new(&message).unwrap(),
+        };
+        let key = OutMsgQueueKey {
+            workchain: 0,
+            prefix: dst.prefix(),
+            hash: *envelope.message_hash(),
+        };
+        let msg = EnqueuedMsg {
+            enqueued_lt: lt,
+            out_msg_envelope: Lazy::new(&envelope).unwrap(),
+        };
+        info.out_queue
+            .set(key, EnqueuedMsgMinLt { min_lt: lt }, msg)
+            .unwrap();
+    }
+    for (shard, lt) in [(0x8000000000000000, 10), (0xc000000000000000, 20)] {
+        info.proc_info
+            .set(
+                ProcessedUptoKey { shard, mc_seqno: 1 },
+                ProcessedUpto {
+                    last_msg_lt: lt,
+                    last_msg_hash: HashBytes::ZERO,
+                },
+            )
+            .unwrap();
+    }
+    for (prefix, import_lt) in [(0x1100000000000000, 30), (0x9900000000000000, 40)] {
+        info.ihr_pending
+            .set(
+                IhrPendingKey {
+                    prefix,
+                    hash: HashBytes::ZERO,
+                },
+                IhrPendingSince { import_lt },
+            )
+            .unwrap();
+    }
+
+    let state = ShardStateUnsplit {
+        shard_ident: ShardIdent::new_full(0),
+        custom: None,
+        out_msg_queue_info: Lazy::new(&info).unwrap(),
+        ..masterchain_state
+    };
+
+    let (left, right) = state.split().unwrap();
+    let left_info = left.load_out_msg_queue_info().unwrap();
+    let right_info = right.load_out_msg_queue_info().unwrap();
+
+    let queue_lts = |info: &OutMsgQueueInfo| {
+        let mut lts = info
+            .out_queue
+            .values()
+            .map(|entry| entry.unwrap().1.enqueued_lt)
+            .collect::<Vec<_>>();
+        lts.sort_unstable();
+        lts
+    };
+    assert_eq!(queue_lts(&left_info), [100, 200, 600]);
+    assert_eq!(queue_lts(&right_info), [300, 400, 500]);
+    assert_eq!(left_info.out_queue.root_extra().min_lt, 100);
+    assert_eq!(right_info.out_queue.root_extra().min_lt, 300);
+
+    // Each message is kept in exactly one child
+    for key in info.out_queue.keys() {
+        let key = key.unwrap();
+        let in_left = left_info.out_queue.get(key).unwrap().is_some();
+        let in_right = right_info.out_queue.get(key).unwrap().is_some();
+        assert!(in_left ^ in_right);
+    }
+
+    // Processed info of the parent shard is kept in both children
+    let proc_shards = |info: &OutMsgQueueInfo| {
+        info.proc_info
+            .keys()
+            .map(|key| key.unwrap().shard)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(proc_shards(&left_info), [0x8000000000000000]);
+    assert_eq!(
+        proc_shards(&right_info),
+        [0x8000000000000000, 0xc000000000000000]
+    );
+
+    // Pending IHR messages are partitioned by destination
+    let ihr_prefixes = |info: &OutMsgQueueInfo| {
+        info.ihr_pending
+            .keys()
+            .map(|key| key.unwrap().prefix)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ihr_prefixes(&left_info), [0x1100000000000000]);
+    assert_eq!(ihr_prefixes(&right_info), [0x9900000000000000]);
+
+    // Merge restores the original queue
+    let merged = ShardStateUnsplit::merge(&left, &right).unwrap();
+    assert_eq!(merged.load_out_msg_queue_info().unwrap(), info);
+}
@@ -11,8 +11,10 @@ use crate::models::message::Message;
 use crate::models::Lazy;
 
 pub use self::phases::*;
+pub use self::trace::*;
 
 mod phases;
+mod trace;
 
 #[cfg(test)]
 mod tests;
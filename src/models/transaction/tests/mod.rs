@@ -86,3 +86,111 @@ fn tick_tx() {
 fn tock_tx() {
     check_tx(include_bytes!("tock_tx.boc"));
 }
+
+#[test]
+fn trace_builder() -> anyhow::Result<()> {
+    use crate::models::{CurrencyCollection, IntAddr, MessageBuilder};
+
+    let base = Boc::decode(include_bytes!("ordinary_tx_with_external.boc"))?;
+    let base = base.parse::<Transaction>()?;
+
+    let make_tx = |lt: u64, in_msg: &Cell, out_msgs: &[&Cell]| -> anyhow::Result<Transaction> {
+        let mut tx = base.clone();
+        tx.lt = lt;
+        tx.in_msg = Some(in_msg.clone());
+        tx.out_msgs = Dict::new();
+        for (i, msg) in out_msgs.iter().enumerate() {
+            tx.out_msgs.set(Uint15::new(i as u16), (*msg).clone())?;
+        }
+        tx.out_msg_count = Uint15::new(out_msgs.len() as u16);
+        Ok(tx)
+    };
+    let int_msg = |lt: u64, bounced: bool| -> anyhow::Result<Cell> {
+        let mut builder = MessageBuilder::internal(
+            IntAddr::default(),
+            IntAddr::default(),
+            CurrencyCollection::ZERO,
+        )
+        .with_created_lt(lt);
+        if let crate::models::MsgInfo::Int(info) = builder.info_mut() {
+            info.bounced = bounced;
+        }
+        Ok(builder.build()?.0)
+    };
+
+    let ext_in = MessageBuilder::external_in(IntAddr::default()).build()?.0;
+    let ext_out = MessageBuilder::external_out(IntAddr::default()).build()?.0;
+    let to_b = int_msg(11, false)?;
+    let to_c = int_msg(12, false)?;
+    let unresolved = int_msg(13, false)?;
+    let to_d = int_msg(21, false)?;
+    let bounced = int_msg(22, true)?;
+    let unknown = int_msg(1, false)?;
+
+    let tx_a = make_tx(10, &ext_in, &[&to_b, &to_c, &ext_out, &unresolved])?;
+    let tx_b = make_tx(20, &to_b, &[&to_d])?;
+    let tx_c = make_tx(21, &to_c, &[&bounced])?;
+    let tx_d = make_tx(30, &to_d, &[])?;
+    let tx_e = make_tx(31, &bounced, &[])?;
+    let tx_f = make_tx(5, &unknown, &[])?;
+
+    let mut builder = TraceBuilder::new();
+    for tx in [&tx_e, &tx_d, &tx_c, &tx_b, &tx_a, &tx_f] {
+        builder.add_transaction(tx.clone());
+    }
+    assert_eq!(builder.len(), 6);
+
+    let traces = builder.clone().build()?;
+    assert_eq!(traces.len(), 2);
+
+    // Separate trace for the transaction without known parent
+    let trace = &traces[0];
+    assert_eq!(trace.nodes().len(), 1);
+    assert_eq!(trace.root().transaction, tx_f);
+    assert_eq!(trace.root_external_msg(), None);
+    assert!(trace.is_complete());
+
+    // Main trace
+    let trace = &traces[1];
+    assert_eq!(trace.root_external_msg(), Some(ext_in.repr_hash()));
+    assert_eq!(trace.max_depth(), 2);
+    assert!(!trace.is_complete());
+    assert_eq!(
+        trace.unresolved_msgs().collect::<Vec<_>>(),
+        [unresolved.repr_hash()]
+    );
+
+    let nodes = trace.nodes();
+    let expected = [
+        (&tx_a, None, 0),
+        (&tx_b, Some(0), 1),
+        (&tx_d, Some(1), 2),
+        (&tx_c, Some(0), 1),
+        (&tx_e, Some(3), 2),
+    ];
+    assert_eq!(nodes.len(), expected.len());
+    for (node, (tx, parent, depth)) in nodes.iter().zip(expected) {
+        assert_eq!(&node.transaction, tx);
+        assert_eq!(node.parent, parent);
+        assert_eq!(node.depth, depth);
+    }
+    assert_eq!(nodes[0].children, [1, 3]);
+    assert_eq!(nodes[0].out_msgs.len(), 4);
+
+    let bounced_nodes = trace.bounced().collect::<Vec<_>>();
+    assert_eq!(bounced_nodes.len(), 1);
+    assert_eq!(bounced_nodes[0].transaction, tx_e);
+
+    // Same message processed twice
+    let err = builder.clone().with_transaction(tx_b.clone()).build();
+    assert!(matches!(err, Err(Error::InvalidData)));
+
+    // Message processed before it was created
+    let err = TraceBuilder::new()
+        .with_transaction(tx_a)
+        .with_transaction(make_tx(1, &to_b, &[])?)
+        .build();
+    assert!(matches!(err, Err(Error::InvalidData)));
+
+    Ok(())
+}
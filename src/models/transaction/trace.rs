@@ -0,0 +1,300 @@
+use std::collections::hash_map;
+use std::collections::HashMap;
+
+use crate::cell::*;
+use crate::error::Error;
+
+use crate::models::message::{MsgInfo, MsgType};
+
+use super::Transaction;
+
+/// Links transactions into cascade trees.
+///
+/// Outgoing internal messages of each transaction are matched
+/// with incoming messages of later transactions by their hashes.
+///
+/// # Example
+///
+/// ```
+/// # use everscale_types::models::{Transaction, TraceBuilder};
+/// # fn get_transactions() -> Vec<Transaction> { Vec::new() }
+/// # fn main() -> anyhow::Result<()> {
+/// let mut builder = TraceBuilder::new();
+/// for tx in get_transactions() {
+///     builder.add_transaction(tx);
+/// }
+///
+/// for trace in builder.build()? {
+///     println!("root external message: {:?}", trace.root_external_msg());
+///     println!("complete: {}", trace.is_complete());
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TraceBuilder {
+    transactions: Vec<Transaction>,
+}
+
+impl TraceBuilder {
+    /// Creates an empty trace builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of added transactions.
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns `true` if no transactions were added.
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Adds a transaction to the builder.
+    pub fn add_transaction(&mut self, transaction: Transaction) {
+        self.transactions.push(transaction);
+    }
+
+    /// Adds a transaction to the builder.
+    pub fn with_transaction(mut self, transaction: Transaction) -> Self {
+        self.add_transaction(transaction);
+        self
+    }
+
+    /// Links all added transactions into traces.
+    ///
+    /// Returns a list of traces sorted by the logical time of the root transaction.
+    /// Transactions which were triggered by messages of transactions outside
+    /// of the provided set become roots of their own traces.
+    ///
+    /// Fails with [`Error::InvalidData`] if the same incoming message was
+    /// processed by several transactions, or if a transaction processes
+    /// a message created by a later transaction.
+    pub fn build(self) -> Result<Vec<Trace>, Error> {
+        struct Item {
+            transaction: Transaction,
+            in_msg: Option<TraceMsg>,
+            out_msgs: Vec<TraceMsg>,
+            children: Vec<usize>,
+            unresolved_msgs: Vec<HashBytes>,
+            has_parent: bool,
+        }
+
+        let mut items = Vec::with_capacity(self.transactions.len());
+        let mut by_in_msg = HashMap::<HashBytes, usize>::with_capacity(self.transactions.len());
+
+        for (index, transaction) in self.transactions.into_iter().enumerate() {
+            let in_msg = match &transaction.in_msg {
+                Some(cell) => {
+                    let msg = ok!(TraceMsg::from_cell(cell));
+                    match by_in_msg.entry(msg.hash) {
+                        hash_map::Entry::Vacant(entry) => {
+                            entry.insert(index);
+                        }
+                        hash_map::Entry::Occupied(_) => return Err(Error::InvalidData),
+                    }
+                    Some(msg)
+                }
+                None => None,
+            };
+
+            let mut out_msgs = Vec::with_capacity(transaction.out_msg_count.into_inner() as _);
+            for cell in transaction.out_msgs.values() {
+                out_msgs.push(ok!(TraceMsg::from_cell(&ok!(cell))));
+            }
+
+            items.push(Item {
+                transaction,
+                in_msg,
+                out_msgs,
+                children: Vec::new(),
+                unresolved_msgs: Vec::new(),
+                has_parent: false,
+            });
+        }
+
+        // Link outgoing messages with transactions
+        for index in 0..items.len() {
+            let mut unresolved_msgs = Vec::new();
+            let mut children = Vec::new();
+
+            let parent_lt = items[index].transaction.lt;
+            for msg in &items[index].out_msgs {
+                if msg.ty != MsgType::Int {
+                    continue;
+                }
+
+                match by_in_msg.get(&msg.hash) {
+                    Some(&child) if items[child].transaction.lt > parent_lt => {
+                        children.push(child);
+                    }
+                    Some(_) => return Err(Error::InvalidData),
+                    None => unresolved_msgs.push(msg.hash),
+                }
+            }
+
+            for &child in &children {
+                items[child].has_parent = true;
+            }
+            items[index].children = children;
+            items[index].unresolved_msgs = unresolved_msgs;
+        }
+
+        let mut roots = (0..items.len())
+            .filter(|&index| !items[index].has_parent)
+            .collect::<Vec<_>>();
+        roots.sort_by_key(|&index| items[index].transaction.lt);
+
+        // Build traces
+        let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+
+        let mut traces = Vec::with_capacity(roots.len());
+        for root in roots {
+            let mut nodes = Vec::<TraceNode>::new();
+
+            // (item index, parent node index, depth)
+            let mut stack = vec![(root, None::<usize>, 0)];
+            while let Some((index, parent, depth)) = stack.pop() {
+                // NOTE: all nodes have at most one parent
+                // and lt strictly increases along the edges
+                let Some(item) = items[index].take() else {
+                    continue;
+                };
+
+                let node_index = nodes.len();
+                if let Some(parent) = parent {
+                    nodes[parent].children.push(node_index);
+                }
+
+                for &child in item.children.iter().rev() {
+                    stack.push((child, Some(node_index), depth + 1));
+                }
+
+                nodes.push(TraceNode {
+                    transaction: item.transaction,
+                    parent,
+                    children: Vec::new(),
+                    depth,
+                    in_msg: item.in_msg,
+                    out_msgs: item.out_msgs,
+                    unresolved_msgs: item.unresolved_msgs,
+                });
+            }
+
+            traces.push(Trace { nodes });
+        }
+
+        Ok(traces)
+    }
+}
+
+/// A tree of transactions triggered by a single root transaction.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    nodes: Vec<TraceNode>,
+}
+
+impl Trace {
+    /// Returns the root node of the trace.
+    pub fn root(&self) -> &TraceNode {
+        &self.nodes[0]
+    }
+
+    /// Returns all nodes of the trace in depth-first order.
+    ///
+    /// The root node is always the first one.
+    pub fn nodes(&self) -> &[TraceNode] {
+        &self.nodes
+    }
+
+    /// Returns a node by its index.
+    pub fn get(&self, index: usize) -> Option<&TraceNode> {
+        self.nodes.get(index)
+    }
+
+    /// Returns the hash of the external message which started the trace.
+    pub fn root_external_msg(&self) -> Option<&HashBytes> {
+        match &self.root().in_msg {
+            Some(msg) if msg.ty == MsgType::ExtIn => Some(&msg.hash),
+            _ => None,
+        }
+    }
+
+    /// Returns the maximum depth of the trace.
+    pub fn max_depth(&self) -> usize {
+        self.nodes
+            .iter()
+            .map(|node| node.depth)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over the nodes which were triggered by bounced messages.
+    pub fn bounced(&self) -> impl Iterator<Item = &TraceNode> {
+        self.nodes.iter().filter(|node| node.is_bounced())
+    }
+
+    /// Returns an iterator over the hashes of outgoing internal messages
+    /// which were not processed by any transaction of the trace.
+    pub fn unresolved_msgs(&self) -> impl Iterator<Item = &HashBytes> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.unresolved_msgs.iter())
+    }
+
+    /// Returns `true` if all outgoing internal messages were processed.
+    pub fn is_complete(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|node| node.unresolved_msgs.is_empty())
+    }
+}
+
+/// Transaction with its position in the trace.
+#[derive(Debug, Clone)]
+pub struct TraceNode {
+    /// Transaction of this node.
+    pub transaction: Transaction,
+    /// Index of the parent node.
+    pub parent: Option<usize>,
+    /// Indices of the child nodes, in order of outgoing messages.
+    pub children: Vec<usize>,
+    /// Distance from the root node.
+    pub depth: usize,
+    /// Incoming message info.
+    pub in_msg: Option<TraceMsg>,
+    /// Outgoing messages info, in order of their logical time.
+    pub out_msgs: Vec<TraceMsg>,
+    /// Hashes of outgoing internal messages without a matching transaction.
+    pub unresolved_msgs: Vec<HashBytes>,
+}
+
+impl TraceNode {
+    /// Returns `true` if the transaction was triggered by a bounced message.
+    pub fn is_bounced(&self) -> bool {
+        matches!(&self.in_msg, Some(msg) if msg.bounced)
+    }
+}
+
+/// Brief message info in the trace.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TraceMsg {
+    /// Message cell hash.
+    pub hash: HashBytes,
+    /// Message type.
+    pub ty: MsgType,
+    /// Whether this message is a bounced internal message.
+    pub bounced: bool,
+}
+
+impl TraceMsg {
+    fn from_cell(cell: &Cell) -> Result<Self, Error> {
+        let info = ok!(cell.parse::<MsgInfo>());
+        Ok(Self {
+            hash: *cell.repr_hash(),
+            ty: info.ty(),
+            bounced: matches!(info, MsgInfo::Int(info) if info.bounced),
+        })
+    }
+}